@@ -0,0 +1,103 @@
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use num_bigint::BigInt;
+use num_traits::{FromPrimitive, ToPrimitive};
+
+use crate::environment::Environment;
+use crate::lox_decimal;
+use crate::lox_error::LoxError;
+use crate::lox_value::LoxValue;
+use crate::native_function::NativeFunction;
+
+pub fn define_globals(globals: &mut Environment) {
+    define(
+        globals,
+        NativeFunction::new("clock", 0, |_, _| {
+            match SystemTime::now().duration_since(UNIX_EPOCH) {
+                Ok(duration) => Ok(LoxValue::Number(duration.as_secs_f64())),
+                Err(_) => Err(LoxError::RuntimeError(
+                    "System clock is set before the epoch".to_string(),
+                )),
+            }
+        }),
+    );
+
+    define(
+        globals,
+        NativeFunction::new("str", 1, |_, arguments| {
+            Ok(LoxValue::String(format!("{}", arguments[0])))
+        }),
+    );
+
+    define(
+        globals,
+        NativeFunction::new("int", 1, |_, arguments| match &arguments[0] {
+            LoxValue::Integer(_) | LoxValue::BigInteger(_) => Ok(arguments[0].clone()),
+            LoxValue::Decimal(value) => Ok(LoxValue::integer(BigInt::from(
+                value.trunc().to_i128().unwrap(),
+            ))),
+            LoxValue::Number(value) => match BigInt::from_f64(value.trunc()) {
+                Some(value) => Ok(LoxValue::integer(value)),
+                None => Err(LoxError::RuntimeError(format!(
+                    "Can't convert {} to an integer.",
+                    value
+                ))),
+            },
+            LoxValue::String(value) => match BigInt::from_str(value.trim()) {
+                Ok(value) => Ok(LoxValue::integer(value)),
+                Err(_) => Err(LoxError::RuntimeError(format!(
+                    "Can't convert '{}' to an integer.",
+                    value
+                ))),
+            },
+            value => Err(LoxError::RuntimeError(format!(
+                "Can't convert {} to an integer.",
+                value
+            ))),
+        }),
+    );
+
+    define(
+        globals,
+        NativeFunction::new("decimal", 1, |_, arguments| {
+            Ok(LoxValue::Decimal(lox_decimal::from_value(&arguments[0])?))
+        }),
+    );
+
+    define(
+        globals,
+        NativeFunction::new("float", 1, |_, arguments| match &arguments[0] {
+            LoxValue::String(value) => match f64::from_str(value.trim()) {
+                Ok(value) => Ok(LoxValue::Number(value)),
+                Err(_) => Err(LoxError::RuntimeError(format!(
+                    "Can't convert '{}' to a float.",
+                    value
+                ))),
+            },
+            value => match value.to_float() {
+                Some(float) => Ok(LoxValue::Number(float)),
+                None => Err(LoxError::RuntimeError(format!(
+                    "Can't convert {} to a float.",
+                    value
+                ))),
+            },
+        }),
+    );
+
+    define(
+        globals,
+        NativeFunction::new("doc", 1, |_, arguments| {
+            let doc = match &arguments[0] {
+                LoxValue::Function(function) => function.doc(),
+                LoxValue::Class(class) => class.doc(),
+                _ => None,
+            };
+            Ok(doc.map_or(LoxValue::Nil, LoxValue::String))
+        }),
+    );
+}
+
+fn define(globals: &mut Environment, function: NativeFunction) {
+    globals.define(function.name(), LoxValue::NativeFunction(function));
+}
@@ -1,7 +1,8 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
+use crate::lox_error::LoxError;
 use crate::lox_value::LoxValue;
 use crate::token::Token;
 
@@ -9,12 +10,14 @@ use crate::token::Token;
 pub struct Environment {
     pub enclosing: Option<Rc<RefCell<Environment>>>,
     values: HashMap<String, LoxValue>,
+    constants: HashSet<String>,
 }
 
 impl Environment {
     pub fn new() -> Environment {
         Environment {
             values: HashMap::new(),
+            constants: HashSet::new(),
             enclosing: None,
         }
     }
@@ -22,14 +25,42 @@ impl Environment {
     pub fn new_with(enclosing: Rc<RefCell<Environment>>) -> Environment {
         Environment {
             values: HashMap::new(),
+            constants: HashSet::new(),
             enclosing: Some(enclosing),
         }
     }
 
     pub fn define(&mut self, name: String, value: LoxValue) {
+        self.constants.remove(&name);
         self.values.insert(name, value);
     }
 
+    pub fn define_constant(&mut self, name: String, value: LoxValue) {
+        self.constants.insert(name.clone());
+        self.values.insert(name, value);
+    }
+
+    // Redeclaring a name replaces it, but only `const` can replace a constant
+    pub fn declare(
+        &mut self,
+        name: String,
+        value: LoxValue,
+        constant: bool,
+    ) -> Result<(), LoxError> {
+        if !constant && self.constants.contains(&name) {
+            return Err(LoxError::RuntimeError(format!(
+                "Cannot redeclare constant '{}'.",
+                name
+            )));
+        }
+        if constant {
+            self.define_constant(name, value);
+        } else {
+            self.define(name, value);
+        }
+        Ok(())
+    }
+
     pub fn get(&mut self, name: Token) -> LoxValue {
         if let Some(value) = self.values.get(&name.lexeme()) {
             value.clone()
@@ -40,17 +71,23 @@ impl Environment {
         }
     }
 
-    pub fn assign(&mut self, name: Token, value: LoxValue) {
+    pub fn assign(&mut self, name: Token, value: LoxValue) -> Result<(), LoxError> {
         let variable = name.lexeme();
-        match self.values.insert(variable, value.clone()) {
-            Some(_) => {}
-            None => {
-                if let Some(enclosing) = &self.enclosing {
-                    (*enclosing.borrow_mut()).assign(name, value.clone());
-                } else {
-                    panic!("Variable did not exist!");
-                }
-            }
+        if self.constants.contains(&variable) {
+            Err(LoxError::RuntimeError(format!(
+                "Cannot assign to constant '{}'.",
+                variable
+            )))
+        } else if let Some(slot) = self.values.get_mut(&variable) {
+            *slot = value;
+            Ok(())
+        } else if let Some(enclosing) = &self.enclosing {
+            (*enclosing.borrow_mut()).assign(name, value)
+        } else {
+            Err(LoxError::RuntimeError(format!(
+                "Undefined variable '{}'.",
+                variable
+            )))
         }
     }
 
@@ -4,7 +4,9 @@ use uuid::Uuid;
 
 use crate::lox_error::LoxError;
 use crate::lox_value::LoxValue;
-use crate::token::Token;
+use crate::pattern::Pattern;
+use crate::stmt::Function;
+use crate::token::{Token, TokenType};
 
 pub type BoxedExpr = Box<dyn Expr>;
 pub type LoxResult = Result<LoxValue, LoxError>;
@@ -61,6 +63,23 @@ pub trait Visitor {
     fn visit_assignment(&mut self, expr: &Assign) -> Result<Self::Value, LoxError>;
     fn visit_logical(&mut self, expr: &Logical) -> Result<Self::Value, LoxError>;
     fn visit_call(&mut self, expr: &Call) -> Result<Self::Value, LoxError>;
+    fn visit_get(&mut self, expr: &Get) -> Result<Self::Value, LoxError>;
+    fn visit_set(&mut self, expr: &Set) -> Result<Self::Value, LoxError>;
+    fn visit_this(&mut self, expr: &This) -> Result<Self::Value, LoxError>;
+    fn visit_super(&mut self, expr: &Super) -> Result<Self::Value, LoxError>;
+    fn visit_lambda(&mut self, expr: &Lambda) -> Result<Self::Value, LoxError>;
+    fn visit_list(&mut self, expr: &List) -> Result<Self::Value, LoxError>;
+    fn visit_subscript(&mut self, expr: &Subscript) -> Result<Self::Value, LoxError>;
+    fn visit_subscript_set(&mut self, expr: &SubscriptSet) -> Result<Self::Value, LoxError>;
+    fn visit_slice(&mut self, expr: &Slice) -> Result<Self::Value, LoxError>;
+    fn visit_map(&mut self, expr: &Map) -> Result<Self::Value, LoxError>;
+    fn visit_interpolation(&mut self, expr: &Interpolation) -> Result<Self::Value, LoxError>;
+    fn visit_update(&mut self, expr: &Update) -> Result<Self::Value, LoxError>;
+    fn visit_conditional(&mut self, expr: &Conditional) -> Result<Self::Value, LoxError>;
+    fn visit_optional_chain(&mut self, expr: &OptionalChain) -> Result<Self::Value, LoxError>;
+    fn visit_tuple(&mut self, expr: &Tuple) -> Result<Self::Value, LoxError>;
+    fn visit_assign_pattern(&mut self, expr: &AssignPattern) -> Result<Self::Value, LoxError>;
+    fn visit_range(&mut self, expr: &Range) -> Result<Self::Value, LoxError>;
 }
 
 #[derive(Debug, Clone)]
@@ -97,9 +116,26 @@ impl Visitable for Literal<LoxValue> {
         match self.value() {
             LoxValue::Nil => visitor.visit_nil_literal(self),
             LoxValue::Number(_number) => visitor.visit_number_literal(self),
+            LoxValue::Integer(_integer) => visitor.visit_number_literal(self),
+            LoxValue::BigInteger(_integer) => visitor.visit_number_literal(self),
+            LoxValue::Decimal(_decimal) => visitor.visit_number_literal(self),
             LoxValue::String(_string) => visitor.visit_string_literal(self),
             LoxValue::Boolean(_boolean) => visitor.visit_boolean_literal(self),
             LoxValue::Function(_function) => panic!("Can't evaluate a function as a literal value"),
+            LoxValue::NativeFunction(_function) => {
+                panic!("Can't evaluate a native function as a literal value")
+            }
+            LoxValue::Class(_class) => panic!("Can't evaluate a class as a literal value"),
+            LoxValue::List(_list) => panic!("Can't evaluate a list as a literal value"),
+            LoxValue::Tuple(_tuple) => panic!("Can't evaluate a tuple as a literal value"),
+            LoxValue::Map(_map) => panic!("Can't evaluate a map as a literal value"),
+            LoxValue::Range(_range) => panic!("Can't evaluate a range as a literal value"),
+            LoxValue::Generator(_generator) => {
+                panic!("Can't evaluate a generator as a literal value")
+            }
+            LoxValue::Instance(_instance) => {
+                panic!("Can't evaluate an instance as a literal value")
+            }
         }
     }
 }
@@ -415,6 +451,7 @@ pub struct Call {
     callee: BoxedExpr,
     paren: Token,
     arguments: Vec<BoxedExpr>,
+    optional: bool,
 }
 
 impl Expr for Call {
@@ -435,17 +472,27 @@ impl Visitable for Call {
 
 impl Display for Call {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "call {}", self.callee)
+        if self.optional {
+            write!(f, "call {}?.", self.callee)
+        } else {
+            write!(f, "call {}", self.callee)
+        }
     }
 }
 
 impl Call {
-    pub fn new(callee: BoxedExpr, paren: Token, arguments: Vec<BoxedExpr>) -> Box<Call> {
+    pub fn new(
+        callee: BoxedExpr,
+        paren: Token,
+        arguments: Vec<BoxedExpr>,
+        optional: bool,
+    ) -> Box<Call> {
         Box::new(Call {
             id: Uuid::new_v4(),
             callee,
             paren,
             arguments,
+            optional,
         })
     }
 
@@ -460,4 +507,923 @@ impl Call {
     pub fn arguments(&self) -> Vec<BoxedExpr> {
         self.arguments.clone()
     }
+
+    pub fn optional(&self) -> bool {
+        self.optional
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Get {
+    id: Uuid,
+    object: BoxedExpr,
+    name: Token,
+    optional: bool,
+}
+
+impl Expr for Get {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for Get {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_get(self)
+    }
+}
+
+impl Display for Get {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.optional {
+            write!(f, "{}?.{}", self.object, self.name)
+        } else {
+            write!(f, "{}.{}", self.object, self.name)
+        }
+    }
+}
+
+impl Get {
+    pub fn new(object: BoxedExpr, name: Token, optional: bool) -> Box<Get> {
+        Box::new(Get {
+            id: Uuid::new_v4(),
+            object,
+            name,
+            optional,
+        })
+    }
+
+    pub fn object(&self) -> BoxedExpr {
+        self.object.clone()
+    }
+
+    pub fn name(&self) -> Token {
+        self.name.clone()
+    }
+
+    // `?.` yields nil for the rest of the chain when the object is nil
+    pub fn optional(&self) -> bool {
+        self.optional
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Set {
+    id: Uuid,
+    object: BoxedExpr,
+    name: Token,
+    value: BoxedExpr,
+}
+
+impl Expr for Set {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for Set {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_set(self)
+    }
+}
+
+impl Display for Set {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}.{} = {}", self.object, self.name, self.value)
+    }
+}
+
+impl Set {
+    pub fn new(object: BoxedExpr, name: Token, value: BoxedExpr) -> Box<Set> {
+        Box::new(Set {
+            id: Uuid::new_v4(),
+            object,
+            name,
+            value,
+        })
+    }
+
+    pub fn object(&self) -> BoxedExpr {
+        self.object.clone()
+    }
+
+    pub fn name(&self) -> Token {
+        self.name.clone()
+    }
+
+    pub fn value(&self) -> BoxedExpr {
+        self.value.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct This {
+    id: Uuid,
+    keyword: Token,
+}
+
+impl Expr for This {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for This {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_this(self)
+    }
+}
+
+impl Display for This {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.keyword)
+    }
+}
+
+impl This {
+    pub fn new(keyword: Token) -> Box<This> {
+        Box::new(This {
+            id: Uuid::new_v4(),
+            keyword,
+        })
+    }
+
+    pub fn keyword(&self) -> Token {
+        self.keyword.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Super {
+    id: Uuid,
+    keyword: Token,
+    method: Token,
+}
+
+impl Expr for Super {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for Super {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_super(self)
+    }
+}
+
+impl Display for Super {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}.{}", self.keyword, self.method)
+    }
+}
+
+impl Super {
+    pub fn new(keyword: Token, method: Token) -> Box<Super> {
+        Box::new(Super {
+            id: Uuid::new_v4(),
+            keyword,
+            method,
+        })
+    }
+
+    pub fn keyword(&self) -> Token {
+        self.keyword.clone()
+    }
+
+    pub fn method(&self) -> Token {
+        self.method.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Lambda {
+    id: Uuid,
+    declaration: Function,
+}
+
+impl Expr for Lambda {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for Lambda {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_lambda(self)
+    }
+}
+
+impl Display for Lambda {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "fun ({} params)", self.declaration.params().len())
+    }
+}
+
+impl Lambda {
+    pub fn new(declaration: Function) -> Box<Lambda> {
+        Box::new(Lambda {
+            id: Uuid::new_v4(),
+            declaration,
+        })
+    }
+
+    pub fn declaration(&self) -> Function {
+        self.declaration.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct List {
+    id: Uuid,
+    bracket: Token,
+    elements: Vec<BoxedExpr>,
+}
+
+impl Expr for List {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for List {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_list(self)
+    }
+}
+
+impl Display for List {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let elements: Vec<String> = self.elements.iter().map(|e| format!("{}", e)).collect();
+        write!(f, "[{}]", elements.join(", "))
+    }
+}
+
+impl List {
+    pub fn new(bracket: Token, elements: Vec<BoxedExpr>) -> Box<List> {
+        Box::new(List {
+            id: Uuid::new_v4(),
+            bracket,
+            elements,
+        })
+    }
+
+    pub fn bracket(&self) -> Token {
+        self.bracket.clone()
+    }
+
+    pub fn elements(&self) -> Vec<BoxedExpr> {
+        self.elements.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Subscript {
+    id: Uuid,
+    object: BoxedExpr,
+    bracket: Token,
+    index: BoxedExpr,
+}
+
+impl Expr for Subscript {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for Subscript {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_subscript(self)
+    }
+}
+
+impl Display for Subscript {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}[{}]", self.object, self.index)
+    }
+}
+
+impl Subscript {
+    pub fn new(object: BoxedExpr, bracket: Token, index: BoxedExpr) -> Box<Subscript> {
+        Box::new(Subscript {
+            id: Uuid::new_v4(),
+            object,
+            bracket,
+            index,
+        })
+    }
+
+    pub fn object(&self) -> BoxedExpr {
+        self.object.clone()
+    }
+
+    pub fn bracket(&self) -> Token {
+        self.bracket.clone()
+    }
+
+    pub fn index(&self) -> BoxedExpr {
+        self.index.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SubscriptSet {
+    id: Uuid,
+    object: BoxedExpr,
+    bracket: Token,
+    index: BoxedExpr,
+    value: BoxedExpr,
+}
+
+impl Expr for SubscriptSet {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for SubscriptSet {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_subscript_set(self)
+    }
+}
+
+impl Display for SubscriptSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}[{}] = {}", self.object, self.index, self.value)
+    }
+}
+
+impl SubscriptSet {
+    pub fn new(
+        object: BoxedExpr,
+        bracket: Token,
+        index: BoxedExpr,
+        value: BoxedExpr,
+    ) -> Box<SubscriptSet> {
+        Box::new(SubscriptSet {
+            id: Uuid::new_v4(),
+            object,
+            bracket,
+            index,
+            value,
+        })
+    }
+
+    pub fn object(&self) -> BoxedExpr {
+        self.object.clone()
+    }
+
+    pub fn bracket(&self) -> Token {
+        self.bracket.clone()
+    }
+
+    pub fn index(&self) -> BoxedExpr {
+        self.index.clone()
+    }
+
+    pub fn value(&self) -> BoxedExpr {
+        self.value.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Slice {
+    id: Uuid,
+    object: BoxedExpr,
+    bracket: Token,
+    start: Option<BoxedExpr>,
+    end: Option<BoxedExpr>,
+}
+
+impl Expr for Slice {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for Slice {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_slice(self)
+    }
+}
+
+impl Display for Slice {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let start = self
+            .start
+            .as_ref()
+            .map_or(String::new(), |e| format!("{}", e));
+        let end = self
+            .end
+            .as_ref()
+            .map_or(String::new(), |e| format!("{}", e));
+        write!(f, "{}[{}:{}]", self.object, start, end)
+    }
+}
+
+impl Slice {
+    pub fn new(
+        object: BoxedExpr,
+        bracket: Token,
+        start: Option<BoxedExpr>,
+        end: Option<BoxedExpr>,
+    ) -> Box<Slice> {
+        Box::new(Slice {
+            id: Uuid::new_v4(),
+            object,
+            bracket,
+            start,
+            end,
+        })
+    }
+
+    pub fn object(&self) -> BoxedExpr {
+        self.object.clone()
+    }
+
+    pub fn bracket(&self) -> Token {
+        self.bracket.clone()
+    }
+
+    pub fn start(&self) -> Option<BoxedExpr> {
+        self.start.clone()
+    }
+
+    pub fn end(&self) -> Option<BoxedExpr> {
+        self.end.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Map {
+    id: Uuid,
+    brace: Token,
+    entries: Vec<(BoxedExpr, BoxedExpr)>,
+}
+
+impl Expr for Map {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for Map {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_map(self)
+    }
+}
+
+impl Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let entries: Vec<String> = self
+            .entries
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, value))
+            .collect();
+        write!(f, "{{{}}}", entries.join(", "))
+    }
+}
+
+impl Map {
+    pub fn new(brace: Token, entries: Vec<(BoxedExpr, BoxedExpr)>) -> Box<Map> {
+        Box::new(Map {
+            id: Uuid::new_v4(),
+            brace,
+            entries,
+        })
+    }
+
+    pub fn brace(&self) -> Token {
+        self.brace.clone()
+    }
+
+    pub fn entries(&self) -> Vec<(BoxedExpr, BoxedExpr)> {
+        self.entries.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Interpolation {
+    id: Uuid,
+    parts: Vec<BoxedExpr>,
+}
+
+impl Expr for Interpolation {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for Interpolation {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_interpolation(self)
+    }
+}
+
+impl Display for Interpolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let parts: Vec<String> = self.parts.iter().map(|e| format!("{}", e)).collect();
+        write!(f, "{}", parts.join(" + "))
+    }
+}
+
+impl Interpolation {
+    pub fn new(parts: Vec<BoxedExpr>) -> Box<Interpolation> {
+        Box::new(Interpolation {
+            id: Uuid::new_v4(),
+            parts,
+        })
+    }
+
+    pub fn parts(&self) -> Vec<BoxedExpr> {
+        self.parts.clone()
+    }
+}
+
+// Compound assignments and increments, which read and write their target in one step
+#[derive(Debug, Clone)]
+pub struct Update {
+    id: Uuid,
+    target: BoxedExpr,
+    operator: Token,
+    value: BoxedExpr,
+    prefix: bool,
+}
+
+impl Expr for Update {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for Update {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_update(self)
+    }
+}
+
+impl Display for Update {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.operator.token_type() {
+            TokenType::PlusPlus | TokenType::MinusMinus if self.prefix => {
+                write!(f, "{}{}", self.operator, self.target)
+            }
+            TokenType::PlusPlus | TokenType::MinusMinus => {
+                write!(f, "{}{}", self.target, self.operator)
+            }
+            _ => write!(f, "{} {} {}", self.target, self.operator, self.value),
+        }
+    }
+}
+
+impl Update {
+    pub fn new(target: BoxedExpr, operator: Token, value: BoxedExpr, prefix: bool) -> Box<Update> {
+        Box::new(Update {
+            id: Uuid::new_v4(),
+            target,
+            operator,
+            value,
+            prefix,
+        })
+    }
+
+    pub fn target(&self) -> BoxedExpr {
+        self.target.clone()
+    }
+
+    pub fn operator(&self) -> Token {
+        self.operator.clone()
+    }
+
+    pub fn value(&self) -> BoxedExpr {
+        self.value.clone()
+    }
+
+    // Prefix forms and compound assignments produce the new value, postfix forms the old one
+    pub fn prefix(&self) -> bool {
+        self.prefix
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Conditional {
+    id: Uuid,
+    condition: BoxedExpr,
+    then_branch: BoxedExpr,
+    else_branch: BoxedExpr,
+}
+
+impl Expr for Conditional {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for Conditional {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_conditional(self)
+    }
+}
+
+impl Display for Conditional {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ? {} : {}",
+            self.condition, self.then_branch, self.else_branch
+        )
+    }
+}
+
+impl Conditional {
+    pub fn new(
+        condition: BoxedExpr,
+        then_branch: BoxedExpr,
+        else_branch: BoxedExpr,
+    ) -> Box<Conditional> {
+        Box::new(Conditional {
+            id: Uuid::new_v4(),
+            condition,
+            then_branch,
+            else_branch,
+        })
+    }
+
+    pub fn condition(&self) -> BoxedExpr {
+        self.condition.clone()
+    }
+
+    pub fn then_branch(&self) -> BoxedExpr {
+        self.then_branch.clone()
+    }
+
+    pub fn else_branch(&self) -> BoxedExpr {
+        self.else_branch.clone()
+    }
+}
+
+// Wraps a call chain containing `?.` so a nil link ends the whole chain with nil
+#[derive(Debug, Clone)]
+pub struct OptionalChain {
+    id: Uuid,
+    expression: BoxedExpr,
+}
+
+impl Expr for OptionalChain {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for OptionalChain {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_optional_chain(self)
+    }
+}
+
+impl Display for OptionalChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+impl OptionalChain {
+    pub fn new(expression: BoxedExpr) -> Box<OptionalChain> {
+        Box::new(OptionalChain {
+            id: Uuid::new_v4(),
+            expression,
+        })
+    }
+
+    pub fn expression(&self) -> BoxedExpr {
+        self.expression.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Tuple {
+    id: Uuid,
+    paren: Token,
+    elements: Vec<BoxedExpr>,
+}
+
+impl Expr for Tuple {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for Tuple {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_tuple(self)
+    }
+}
+
+impl Display for Tuple {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let elements: Vec<String> = self.elements.iter().map(|e| format!("{}", e)).collect();
+        if elements.len() == 1 {
+            write!(f, "({},)", elements[0])
+        } else {
+            write!(f, "({})", elements.join(", "))
+        }
+    }
+}
+
+impl Tuple {
+    pub fn new(paren: Token, elements: Vec<BoxedExpr>) -> Box<Tuple> {
+        Box::new(Tuple {
+            id: Uuid::new_v4(),
+            paren,
+            elements,
+        })
+    }
+
+    pub fn paren(&self) -> Token {
+        self.paren.clone()
+    }
+
+    pub fn elements(&self) -> Vec<BoxedExpr> {
+        self.elements.clone()
+    }
+}
+
+// Destructuring assignment such as `(a, b) = (b, a)`
+#[derive(Debug, Clone)]
+pub struct AssignPattern {
+    id: Uuid,
+    pattern: Pattern,
+    value: BoxedExpr,
+}
+
+impl Expr for AssignPattern {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for AssignPattern {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_assign_pattern(self)
+    }
+}
+
+impl Display for AssignPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} = {}", self.pattern, self.value)
+    }
+}
+
+impl AssignPattern {
+    pub fn new(pattern: Pattern, value: BoxedExpr) -> Box<AssignPattern> {
+        Box::new(AssignPattern {
+            id: Uuid::new_v4(),
+            pattern,
+            value,
+        })
+    }
+
+    pub fn pattern(&self) -> Pattern {
+        self.pattern.clone()
+    }
+
+    pub fn value(&self) -> BoxedExpr {
+        self.value.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Range {
+    id: Uuid,
+    start: BoxedExpr,
+    operator: Token,
+    end: BoxedExpr,
+    step: Option<BoxedExpr>,
+}
+
+impl Expr for Range {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Visitable for Range {
+    fn accept(&self, visitor: &mut Visitor<Value = LoxValue>) -> LoxResult {
+        visitor.visit_range(self)
+    }
+}
+
+impl Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.start, self.operator.lexeme(), self.end)?;
+        if let Some(step) = &self.step {
+            write!(f, " step {}", step)?;
+        }
+        Ok(())
+    }
+}
+
+impl Range {
+    pub fn new(
+        start: BoxedExpr,
+        operator: Token,
+        end: BoxedExpr,
+        step: Option<BoxedExpr>,
+    ) -> Box<Range> {
+        Box::new(Range {
+            id: Uuid::new_v4(),
+            start,
+            operator,
+            end,
+            step,
+        })
+    }
+
+    pub fn start(&self) -> BoxedExpr {
+        self.start.clone()
+    }
+
+    pub fn operator(&self) -> Token {
+        self.operator.clone()
+    }
+
+    pub fn end(&self) -> BoxedExpr {
+        self.end.clone()
+    }
+
+    pub fn step(&self) -> Option<BoxedExpr> {
+        self.step.clone()
+    }
+
+    pub fn inclusive(&self) -> bool {
+        self.operator.token_type() == TokenType::DotDotEqual
+    }
 }
@@ -1,33 +1,89 @@
 use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::rc::Rc;
 use uuid::Uuid;
 
+use crate::builtins;
 use crate::environment::Environment;
 use crate::expr::{
-    Assign, Binary, BoxedExpr, Call, Grouping, Literal, Logical, Unary, Variable,
-    Visitor as ExprVisitor,
+    Assign, AssignPattern, Binary, BoxedExpr, Call, Conditional, Expr, Get, Grouping,
+    Interpolation, Lambda, List, Literal, Logical, Map, OptionalChain, Range, Set, Slice,
+    Subscript, SubscriptSet, Super, This, Tuple, Unary, Update, Variable, Visitor as ExprVisitor,
 };
 use crate::lox_callable::LoxCallable;
+use crate::lox_class::LoxClass;
+use crate::lox_decimal;
 use crate::lox_error::LoxError;
 use crate::lox_function::LoxFunction;
+use crate::lox_generator::{self, Suspender};
+use crate::lox_instance::LoxInstance;
+use crate::lox_iterator::LoxIterator;
+use crate::lox_list;
+use crate::lox_map;
+use crate::lox_range::LoxRange;
+use crate::lox_string;
 use crate::lox_value::LoxValue;
+use crate::pattern::Pattern;
 use crate::stmt::{
-    Block, Expression, Function, If, Print, Return, Stmt, Var, Visitor as StmtVisitor, While,
+    Block, Break, Class, Continue, Expression, ForIn, Function, If, Print, Return, Stmt,
+    StmtResult, Throw, Try, Var, VarPattern, Visitor as StmtVisitor, While, Yield,
 };
 use crate::token::{Token, TokenType};
 
+// How a destructuring pattern binds the values it matches
+#[derive(Clone, Copy)]
+enum Binding {
+    Var,
+    Const,
+    Assign,
+}
+
 #[derive(Clone)]
 pub struct Interpreter {
+    globals: Rc<RefCell<Environment>>,
     environment: Rc<RefCell<Environment>>,
-    locals: HashMap<Uuid, usize>,
+    locals: Rc<RefCell<HashMap<Uuid, usize>>>,
+    error_class: LoxClass,
+    // Set on the interpreter running a generator's body, for `yield` to suspend it
+    suspender: Option<Suspender>,
 }
 
 impl Interpreter {
     pub fn new() -> Interpreter {
+        let globals = Rc::new(RefCell::new(Environment::new()));
+        builtins::define_globals(&mut globals.borrow_mut());
+
+        let error_class = LoxClass::new("Error".to_string(), None, HashMap::new());
+        globals
+            .borrow_mut()
+            .define("Error".to_string(), LoxValue::Class(error_class.clone()));
+
+        Interpreter {
+            environment: Rc::clone(&globals),
+            globals,
+            locals: Rc::new(RefCell::new(HashMap::new())),
+            error_class,
+            suspender: None,
+        }
+    }
+
+    // A copy for a generator's body to run on. It starts out in the globals
+    // because the body installs its own closure environment anyway, and
+    // holding on to the caller's scope would keep that scope alive in a cycle
+    // with any variable the generator gets stored in.
+    pub fn for_generator(&self) -> Interpreter {
+        Interpreter {
+            environment: Rc::clone(&self.globals),
+            suspender: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_suspender(self, suspender: Suspender) -> Interpreter {
         Interpreter {
-            environment: Rc::new(RefCell::new(Environment::new())),
-            locals: HashMap::new(),
+            suspender: Some(suspender),
+            ..self
         }
     }
 
@@ -37,11 +93,14 @@ impl Interpreter {
 
     pub fn interpret(&mut self, statements: Vec<Box<dyn Stmt>>) {
         for statement in statements {
-            self.execute(statement);
+            if let Err(error) = self.execute(statement) {
+                eprintln!("{}", error);
+                break;
+            }
         }
     }
 
-    pub fn execute(&mut self, stmt: Box<dyn Stmt>) -> Option<LoxValue> {
+    pub fn execute(&mut self, stmt: Box<dyn Stmt>) -> StmtResult {
         stmt.accept(self)
     }
 
@@ -50,18 +109,46 @@ impl Interpreter {
     }
 
     pub fn resolve(&mut self, expr: BoxedExpr, depth: usize) {
-        self.locals.insert(expr.id(), depth);
+        self.locals.borrow_mut().insert(expr.id(), depth);
     }
 
-    fn look_up_variable(&mut self, _name: Token, expr: &Variable) -> Result<LoxValue, LoxError> {
-        let distance = self.locals.get(&expr.id());
+    // Turns a runtime error raised by a value into a catchable `Error` instance
+    // carrying the message and the line of the token that triggered it.
+    fn locate(&self, error: LoxError, token: &Token) -> LoxError {
+        match error {
+            LoxError::RuntimeError(message) => {
+                let value = LoxInstance::new(self.error_class.clone());
+                if let LoxValue::Instance(instance) = &value {
+                    let mut instance_ref = instance.borrow_mut();
+                    instance_ref.set("message".to_string(), LoxValue::String(message));
+                    instance_ref.set(
+                        "line".to_string(),
+                        LoxValue::Integer(i64::from(token.line())),
+                    );
+                }
+                LoxError::Exception(value, token.line())
+            }
+            error => error,
+        }
+    }
+
+    fn declare(&mut self, name: &Token, value: LoxValue, constant: bool) -> Result<(), LoxError> {
+        let result = self
+            .environment
+            .borrow_mut()
+            .declare(name.lexeme(), value, constant);
+        result.map_err(|error| self.locate(error, name))
+    }
+
+    fn look_up_variable(&mut self, name: Token, expr: &dyn Expr) -> Result<LoxValue, LoxError> {
+        let distance = self.locals.borrow().get(&expr.id()).copied();
         if let Some(dist) = distance {
             let mut env_ref = self.environment.borrow_mut();
-            let value = env_ref.get_at(*dist, expr.name().lexeme());
+            let value = env_ref.get_at(dist, name.lexeme());
             Ok(value)
         } else {
-            let mut env_ref = self.environment.borrow_mut();
-            let value = env_ref.get(expr.name());
+            let mut env_ref = self.globals.borrow_mut();
+            let value = env_ref.get(name);
             Ok(value)
         }
     }
@@ -87,7 +174,15 @@ impl ExprVisitor for Interpreter {
     }
 
     fn visit_logical(&mut self, expr: &Logical) -> Result<Self::Value, LoxError> {
-        let left = self.evaluate(expr.left()).unwrap();
+        let left = self.evaluate(expr.left())?;
+
+        if expr.operator().token_type() == TokenType::QuestionQuestion {
+            return match left {
+                LoxValue::Nil => self.evaluate(expr.right()),
+                _ => Ok(left),
+            };
+        }
+
         let is_truthy = self.is_truthy(left.clone());
 
         if expr.operator().token_type() == TokenType::Or {
@@ -104,50 +199,60 @@ impl ExprVisitor for Interpreter {
     }
 
     fn visit_call(&mut self, expr: &Call) -> Result<Self::Value, LoxError> {
-        let callee = self.evaluate(expr.callee());
-
-        let arguments: Vec<LoxValue> = expr
-            .arguments()
-            .iter()
-            .map(|argument| self.evaluate(argument.clone()).unwrap())
-            .collect();
+        let callee = self.evaluate(expr.callee())?;
+        if expr.optional() && callee == LoxValue::Nil {
+            return Err(LoxError::ShortCircuit);
+        }
 
-        match callee {
-            Ok(callee_value) => match callee_value {
-                LoxValue::Function(function) => function.call(self, arguments),
-                _ => Ok(callee_value),
-            },
-            _ => panic!("Could not evaluate function arguments"),
+        let mut arguments: Vec<LoxValue> = Vec::new();
+        for argument in expr.arguments() {
+            arguments.push(self.evaluate(argument)?);
         }
+
+        let result = self.call_value(callee, arguments);
+        result.map_err(|error| self.locate(error, &expr.paren()))
     }
 
     fn visit_unary(&mut self, expr: &Unary) -> Result<Self::Value, LoxError> {
         let right = self.evaluate(expr.right())?;
+        let operator = expr.clone().operator();
 
-        match expr.clone().operator().token_type() {
+        let result = match operator.token_type() {
             TokenType::Minus => -right,
             TokenType::Bang => !right,
+            TokenType::Tilde => right.bit_not(),
             _ => Ok(LoxValue::Number(0.0)),
-        }
+        };
+        result.map_err(|error| self.locate(error, &operator))
     }
 
     fn visit_binary(&mut self, expr: &Binary) -> Result<Self::Value, LoxError> {
         let left = self.evaluate(expr.left())?;
         let right = self.evaluate(expr.right())?;
+        let operator = expr.clone().operator();
 
-        match expr.clone().operator().token_type() {
-            TokenType::Minus => Ok(left - right),
-            TokenType::Slash => Ok(left / right),
-            TokenType::Star => Ok(left * right),
+        let result = match operator.token_type() {
+            TokenType::Minus => left - right,
+            TokenType::Slash => left / right,
+            TokenType::Star => left * right,
+            TokenType::Percent => left % right,
+            TokenType::TildeSlash => left.int_div(right),
+            TokenType::StarStar => left.pow(right),
+            TokenType::Ampersand => left & right,
+            TokenType::Pipe => left | right,
+            TokenType::Caret => left ^ right,
+            TokenType::LessLess => left << right,
+            TokenType::GreaterGreater => left >> right,
             TokenType::Plus => left + right,
-            TokenType::Greater => Ok(LoxValue::Boolean(left > right)),
-            TokenType::GreaterEqual => Ok(LoxValue::Boolean(left >= right)),
-            TokenType::Less => Ok(LoxValue::Boolean(left < right)),
-            TokenType::LessEqual => Ok(LoxValue::Boolean(left <= right)),
+            TokenType::Greater => self.compare(&left, &right, |o| o == Ordering::Greater),
+            TokenType::GreaterEqual => self.compare(&left, &right, |o| o != Ordering::Less),
+            TokenType::Less => self.compare(&left, &right, |o| o == Ordering::Less),
+            TokenType::LessEqual => self.compare(&left, &right, |o| o != Ordering::Greater),
             TokenType::EqualEqual => Ok(LoxValue::Boolean(left == right)),
             TokenType::BangEqual => Ok(LoxValue::Boolean(left != right)),
             _ => Ok(LoxValue::Number(0.0)),
-        }
+        };
+        result.map_err(|error| self.locate(error, &operator))
     }
 
     fn visit_grouping(&mut self, expr: &Grouping) -> Result<Self::Value, LoxError> {
@@ -158,102 +263,703 @@ impl ExprVisitor for Interpreter {
         self.look_up_variable(expr.name(), expr)
     }
 
+    fn visit_get(&mut self, expr: &Get) -> Result<Self::Value, LoxError> {
+        let result = match self.evaluate(expr.object())? {
+            LoxValue::Nil if expr.optional() => Err(LoxError::ShortCircuit),
+            LoxValue::Instance(instance) => LoxInstance::get(&instance, expr.name()),
+            LoxValue::List(list) => lox_list::method(&list, &expr.name()),
+            LoxValue::Map(map) => lox_map::method(&map, &expr.name()),
+            LoxValue::Decimal(value) => lox_decimal::method(value, &expr.name()),
+            LoxValue::String(string) => lox_string::method(&string, &expr.name()),
+            LoxValue::Generator(generator) => lox_generator::method(&generator, &expr.name()),
+            _ => Err(LoxError::RuntimeError(
+                "Only instances have properties.".to_string(),
+            )),
+        };
+        result.map_err(|error| self.locate(error, &expr.name()))
+    }
+
+    fn visit_set(&mut self, expr: &Set) -> Result<Self::Value, LoxError> {
+        match self.evaluate(expr.object())? {
+            LoxValue::Instance(instance) => {
+                let value = self.evaluate(expr.value())?;
+                instance
+                    .borrow_mut()
+                    .set(expr.name().lexeme(), value.clone());
+                Ok(value)
+            }
+            _ => Err(self.locate(
+                LoxError::RuntimeError("Only instances have fields.".to_string()),
+                &expr.name(),
+            )),
+        }
+    }
+
+    fn visit_this(&mut self, expr: &This) -> Result<Self::Value, LoxError> {
+        self.look_up_variable(expr.keyword(), expr)
+    }
+
+    fn visit_super(&mut self, expr: &Super) -> Result<Self::Value, LoxError> {
+        let distance = self.locals.borrow()[&expr.id()];
+        let mut env_ref = self.environment.borrow_mut();
+        let superclass = env_ref.get_at(distance, "super".to_string());
+        let object = env_ref.get_at(distance - 1, "this".to_string());
+
+        let method = match superclass {
+            LoxValue::Class(superclass) => superclass.find_method(&expr.method().lexeme()),
+            _ => None,
+        };
+
+        match method {
+            Some(method) => Ok(LoxValue::Function(method.bind(object))),
+            None => Err(self.locate(
+                LoxError::RuntimeError(format!("Undefined property '{}'.", expr.method().lexeme())),
+                &expr.method(),
+            )),
+        }
+    }
+
+    fn visit_lambda(&mut self, expr: &Lambda) -> Result<Self::Value, LoxError> {
+        let function = LoxFunction::new(expr.declaration(), Rc::clone(&self.environment), false);
+        Ok(LoxValue::Function(function))
+    }
+
     fn visit_assignment(&mut self, expr: &Assign) -> Result<Self::Value, LoxError> {
-        let value = self.evaluate(expr.value()).unwrap();
-        let distance = self.locals.get(&expr.id());
-        if let Some(dist) = distance {
-            let mut env_ref = self.environment.borrow_mut();
-            env_ref.assign_at(*dist, expr.name().lexeme(), value.clone());
-            Ok(value)
+        let value = self.evaluate(expr.value())?;
+        self.assign_variable(expr.name(), expr, value.clone())?;
+        Ok(value)
+    }
+
+    fn visit_list(&mut self, expr: &List) -> Result<Self::Value, LoxError> {
+        let mut values = Vec::new();
+        for element in expr.elements() {
+            values.push(self.evaluate(element)?);
+        }
+        Ok(lox_list::new(values))
+    }
+
+    fn visit_subscript(&mut self, expr: &Subscript) -> Result<Self::Value, LoxError> {
+        let object = self.evaluate(expr.object())?;
+        let index = self.evaluate(expr.index())?;
+        self.subscript(&object, &index)
+            .map_err(|error| self.locate(error, &expr.bracket()))
+    }
+
+    fn visit_subscript_set(&mut self, expr: &SubscriptSet) -> Result<Self::Value, LoxError> {
+        let object = self.evaluate(expr.object())?;
+        let index = self.evaluate(expr.index())?;
+        let value = self.evaluate(expr.value())?;
+        match self.subscript_set(&object, index, value.clone()) {
+            Ok(()) => Ok(value),
+            Err(error) => Err(self.locate(error, &expr.bracket())),
+        }
+    }
+
+    fn visit_slice(&mut self, expr: &Slice) -> Result<Self::Value, LoxError> {
+        let object = self.evaluate(expr.object())?;
+        let start = match expr.start() {
+            Some(start) => Some(self.evaluate(start)?),
+            None => None,
+        };
+        let end = match expr.end() {
+            Some(end) => Some(self.evaluate(end)?),
+            None => None,
+        };
+        let result = match object {
+            LoxValue::List(list) => lox_list::slice(&list, start, end),
+            LoxValue::String(string) => lox_string::slice(&string, start, end),
+            _ => Err(LoxError::RuntimeError(
+                "Only lists and strings can be sliced.".to_string(),
+            )),
+        };
+        result.map_err(|error| self.locate(error, &expr.bracket()))
+    }
+
+    fn visit_interpolation(&mut self, expr: &Interpolation) -> Result<Self::Value, LoxError> {
+        let mut string = String::new();
+        for part in expr.parts() {
+            string.push_str(&self.evaluate(part)?.to_string());
+        }
+        Ok(LoxValue::String(string))
+    }
+
+    fn visit_map(&mut self, expr: &Map) -> Result<Self::Value, LoxError> {
+        let mut entries = Vec::new();
+        for (key, value) in expr.entries() {
+            entries.push((self.evaluate(key)?, self.evaluate(value)?));
+        }
+        lox_map::new(entries).map_err(|error| self.locate(error, &expr.brace()))
+    }
+
+    fn visit_update(&mut self, expr: &Update) -> Result<Self::Value, LoxError> {
+        let target = expr.target();
+
+        let (current, updated) = if let Some(variable) = target.as_any().downcast_ref::<Variable>()
+        {
+            let current = self.look_up_variable(variable.name(), variable)?;
+            let updated = self.updated(&current, expr)?;
+            self.assign_variable(variable.name(), variable, updated.clone())?;
+            (current, updated)
+        } else if let Some(get) = target.as_any().downcast_ref::<Get>() {
+            let instance = match self.evaluate(get.object())? {
+                LoxValue::Instance(instance) => instance,
+                _ => {
+                    return Err(self.locate(
+                        LoxError::RuntimeError("Only instances have fields.".to_string()),
+                        &get.name(),
+                    ))
+                }
+            };
+            let current = LoxInstance::get(&instance, get.name())
+                .map_err(|error| self.locate(error, &get.name()))?;
+            let updated = self.updated(&current, expr)?;
+            instance
+                .borrow_mut()
+                .set(get.name().lexeme(), updated.clone());
+            (current, updated)
+        } else if let Some(subscript) = target.as_any().downcast_ref::<Subscript>() {
+            let object = self.evaluate(subscript.object())?;
+            let index = self.evaluate(subscript.index())?;
+            let current = self
+                .subscript(&object, &index)
+                .map_err(|error| self.locate(error, &subscript.bracket()))?;
+            let updated = self.updated(&current, expr)?;
+            self.subscript_set(&object, index, updated.clone())
+                .map_err(|error| self.locate(error, &subscript.bracket()))?;
+            (current, updated)
         } else {
-            let mut env_ref = self.environment.borrow_mut();
-            env_ref.assign(expr.name(), value.clone());
-            Ok(value)
+            return Err(self.locate(
+                LoxError::RuntimeError("Invalid assignment target.".to_string()),
+                &expr.operator(),
+            ));
+        };
+
+        if expr.prefix() {
+            Ok(updated)
+        } else {
+            Ok(current)
+        }
+    }
+
+    fn visit_conditional(&mut self, expr: &Conditional) -> Result<Self::Value, LoxError> {
+        let condition = self.evaluate(expr.condition())?;
+
+        match self.is_truthy(condition) {
+            LoxValue::Boolean(true) => self.evaluate(expr.then_branch()),
+            _ => self.evaluate(expr.else_branch()),
+        }
+    }
+
+    fn visit_optional_chain(&mut self, expr: &OptionalChain) -> Result<Self::Value, LoxError> {
+        match self.evaluate(expr.expression()) {
+            Err(LoxError::ShortCircuit) => Ok(LoxValue::Nil),
+            result => result,
         }
     }
+
+    fn visit_tuple(&mut self, expr: &Tuple) -> Result<Self::Value, LoxError> {
+        let mut values = Vec::new();
+        for element in expr.elements() {
+            values.push(self.evaluate(element)?);
+        }
+        Ok(LoxValue::Tuple(Rc::new(values)))
+    }
+
+    fn visit_assign_pattern(&mut self, expr: &AssignPattern) -> Result<Self::Value, LoxError> {
+        let value = self.evaluate(expr.value())?;
+        self.destructure(&expr.pattern(), value.clone(), Binding::Assign)?;
+        Ok(value)
+    }
+
+    fn visit_range(&mut self, expr: &Range) -> Result<Self::Value, LoxError> {
+        let start = self.evaluate(expr.start())?;
+        let end = self.evaluate(expr.end())?;
+        let step = match expr.step() {
+            Some(step) => Some(self.evaluate(step)?),
+            None => None,
+        };
+
+        LoxRange::new(&start, &end, step.as_ref(), expr.inclusive())
+            .map_err(|error| self.locate(error, &expr.operator()))
+    }
 }
 
 impl StmtVisitor for Interpreter {
-    type Value = Option<LoxValue>;
+    type Value = StmtResult;
 
-    fn visit_expression_statement(&mut self, stmt: &Expression) -> Option<LoxValue> {
-        self.evaluate(stmt.clone().expr()).ok();
-        None
+    fn visit_expression_statement(&mut self, stmt: &Expression) -> StmtResult {
+        self.evaluate(stmt.clone().expr())?;
+        Ok(None)
     }
 
-    fn visit_if_statement(&mut self, stmt: &If) -> Option<LoxValue> {
-        let condition = self.evaluate(stmt.condition()).unwrap();
+    fn visit_if_statement(&mut self, stmt: &If) -> StmtResult {
+        let condition = self.evaluate(stmt.condition())?;
         let is_truthy = self.is_truthy(condition);
         match is_truthy {
             LoxValue::Boolean(true) => self.execute(stmt.then_branch()),
             _ => {
                 if let Some(else_branch) = stmt.else_branch() {
-                    self.execute(else_branch);
+                    return self.execute(else_branch);
                 }
-                None
+                Ok(None)
             }
         }
     }
 
-    fn visit_print_statement(&mut self, stmt: &Print) -> Option<LoxValue> {
-        let value = self.evaluate(stmt.clone().expr());
-        println!("{}", value.unwrap());
-        None
+    fn visit_print_statement(&mut self, stmt: &Print) -> StmtResult {
+        let value = self.evaluate(stmt.clone().expr())?;
+        println!("{}", value);
+        Ok(None)
     }
 
-    fn visit_return_statement(&mut self, stmt: &Return) -> Option<LoxValue> {
-        if let Ok(value) = self.evaluate(stmt.value()) {
-            Some(value)
-        } else {
-            None
+    fn visit_return_statement(&mut self, stmt: &Return) -> StmtResult {
+        let value = match stmt.value() {
+            Some(value) => self.evaluate(value)?,
+            None => LoxValue::Nil,
+        };
+        Ok(Some(value))
+    }
+
+    fn visit_yield_statement(&mut self, stmt: &Yield) -> StmtResult {
+        let value = match stmt.value() {
+            Some(value) => self.evaluate(value)?,
+            None => LoxValue::Nil,
+        };
+
+        match self.suspender {
+            Some(suspender) => {
+                suspender.suspend(value);
+                Ok(None)
+            }
+            None => Err(self.locate(
+                LoxError::RuntimeError("Can only yield inside a generator.".to_string()),
+                &stmt.keyword(),
+            )),
         }
     }
 
-    fn visit_var_statement(&mut self, stmt: &Var) -> Option<LoxValue> {
+    fn visit_var_statement(&mut self, stmt: &Var) -> StmtResult {
         let mut value = LoxValue::Nil;
         if let Some(initializer) = stmt.initializer() {
-            if let Ok(initializer) = self.evaluate(initializer) {
-                value = initializer;
-            }
+            value = self.evaluate(initializer)?;
         }
-        let mut env_ref = self.environment.borrow_mut();
-        env_ref.define(stmt.name().lexeme(), value);
-        None
+        self.declare(&stmt.name(), value, stmt.constant())?;
+        Ok(None)
     }
 
-    fn visit_block_statement(&mut self, stmt: &Block) -> Option<LoxValue> {
+    fn visit_var_pattern_statement(&mut self, stmt: &VarPattern) -> StmtResult {
+        let value = self.evaluate(stmt.initializer())?;
+        let binding = if stmt.constant() {
+            Binding::Const
+        } else {
+            Binding::Var
+        };
+        self.destructure(&stmt.pattern(), value, binding)?;
+        Ok(None)
+    }
+
+    fn visit_block_statement(&mut self, stmt: &Block) -> StmtResult {
         let env_ref = Rc::clone(&self.environment);
         self.execute_block(
             stmt.statements(),
             Rc::new(RefCell::new(Environment::new_with(env_ref))),
-        );
-        None
+        )
     }
 
-    fn visit_while_statement(&mut self, stmt: &While) -> Option<LoxValue> {
+    fn visit_while_statement(&mut self, stmt: &While) -> StmtResult {
         let truth = LoxValue::Boolean(true);
-        while self.evaluate(stmt.condition()).unwrap() == truth {
-            self.execute(stmt.body());
+        loop {
+            let condition = self.evaluate(stmt.condition())?;
+            if self.is_truthy(condition) != truth {
+                break;
+            }
+
+            match self.execute(stmt.body()) {
+                Ok(None) | Err(LoxError::Continue) => {}
+                Err(LoxError::Break) => break,
+                result => return result,
+            }
+
+            if let Some(increment) = stmt.increment() {
+                self.evaluate(increment)?;
+            }
         }
-        None
+        Ok(None)
     }
 
-    fn visit_function_statement(&mut self, stmt: &Function) -> Option<LoxValue> {
-        let function = LoxFunction::new(stmt.clone(), Rc::clone(&self.environment));
-        let mut env_ref = self.environment.borrow_mut();
-        env_ref.define(stmt.name().lexeme(), LoxValue::Function(function));
-        None
+    fn visit_for_in_statement(&mut self, stmt: &ForIn) -> StmtResult {
+        let iterable = self.evaluate(stmt.iterable())?;
+        let mut iterator = LoxIterator::new(self, iterable)
+            .map_err(|error| self.locate(error, &stmt.keyword()))?;
+
+        let pattern = stmt.pattern();
+        let binding = if stmt.constant() {
+            Binding::Const
+        } else {
+            Binding::Var
+        };
+        loop {
+            let value = match iterator.next(self) {
+                Ok(Some(value)) => value,
+                Ok(None) => break,
+                Err(error) => return Err(self.locate(error, &stmt.keyword())),
+            };
+
+            // Each pass gets its own environment so closures capture that pass's value
+            let previous = Rc::clone(&self.environment);
+            self.environment = Rc::new(RefCell::new(Environment::new_with(Rc::clone(&previous))));
+            let result = self
+                .destructure(&pattern, value, binding)
+                .and_then(|_| self.execute(stmt.body()));
+            self.environment = previous;
+
+            match result {
+                Ok(None) | Err(LoxError::Continue) => {}
+                Err(LoxError::Break) => break,
+                result => return result,
+            }
+        }
+        Ok(None)
+    }
+
+    fn visit_try_statement(&mut self, stmt: &Try) -> StmtResult {
+        let result = self.execute_block(
+            stmt.body(),
+            Rc::new(RefCell::new(Environment::new_with(Rc::clone(
+                &self.environment,
+            )))),
+        );
+
+        let result = match (result, stmt.catch_name(), stmt.catch_body()) {
+            (Err(error), Some(name), Some(catch_body)) => {
+                let error = self.locate(error, &stmt.keyword());
+                match error {
+                    LoxError::Exception(value, _) => {
+                        let mut environment = Environment::new_with(Rc::clone(&self.environment));
+                        environment.define(name.lexeme(), value);
+                        self.execute_block(catch_body, Rc::new(RefCell::new(environment)))
+                    }
+                    error => Err(error),
+                }
+            }
+            (result, _, _) => result,
+        };
+
+        if let Some(finally_body) = stmt.finally_body() {
+            let finally_result = self.execute_block(
+                finally_body,
+                Rc::new(RefCell::new(Environment::new_with(Rc::clone(
+                    &self.environment,
+                )))),
+            );
+            if let Ok(None) = finally_result {
+                return result;
+            }
+            return finally_result;
+        }
+
+        result
+    }
+
+    fn visit_throw_statement(&mut self, stmt: &Throw) -> StmtResult {
+        let value = self.evaluate(stmt.value())?;
+        Err(LoxError::Exception(value, stmt.keyword().line()))
+    }
+
+    fn visit_break_statement(&mut self, _stmt: &Break) -> StmtResult {
+        Err(LoxError::Break)
+    }
+
+    fn visit_continue_statement(&mut self, _stmt: &Continue) -> StmtResult {
+        Err(LoxError::Continue)
+    }
+
+    fn visit_function_statement(&mut self, stmt: &Function) -> StmtResult {
+        let function = LoxFunction::new(stmt.clone(), Rc::clone(&self.environment), false);
+        self.declare(&stmt.name(), LoxValue::Function(function), false)?;
+        Ok(None)
+    }
+
+    fn visit_class_statement(&mut self, stmt: &Class) -> StmtResult {
+        let superclass = match stmt.superclass() {
+            Some(superclass_expr) => match self.evaluate(Box::new(superclass_expr.clone()))? {
+                LoxValue::Class(superclass) => Some(superclass),
+                _ => {
+                    return Err(self.locate(
+                        LoxError::RuntimeError("Superclass must be a class.".to_string()),
+                        &superclass_expr.name(),
+                    ))
+                }
+            },
+            None => None,
+        };
+
+        let enclosing = Rc::clone(&self.environment);
+        if let Some(superclass) = &superclass {
+            let mut environment = Environment::new_with(Rc::clone(&enclosing));
+            environment.define("super".to_string(), LoxValue::Class(superclass.clone()));
+            self.environment = Rc::new(RefCell::new(environment));
+        }
+
+        let mut methods = HashMap::new();
+        for method in stmt.methods() {
+            let is_initializer = method.name().lexeme() == "init";
+            let function =
+                LoxFunction::new(method.clone(), Rc::clone(&self.environment), is_initializer);
+            methods.insert(method.name().lexeme(), function);
+        }
+
+        self.environment = enclosing;
+
+        let class = LoxClass::new(stmt.name().lexeme(), superclass, methods).with_doc(stmt.doc());
+        self.declare(&stmt.name(), LoxValue::Class(class), false)?;
+        Ok(None)
     }
 }
 
 impl Interpreter {
+    pub fn call_value(
+        &mut self,
+        callee: LoxValue,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, LoxError> {
+        match callee {
+            LoxValue::Function(function) => {
+                let name = function.name();
+                self.call(function, name, arguments)
+            }
+            LoxValue::NativeFunction(function) => {
+                let name = function.name();
+                self.call(function, name, arguments)
+            }
+            LoxValue::Class(class) => {
+                let name = class.name();
+                self.call(class, name, arguments)
+            }
+            _ => Err(LoxError::RuntimeError(
+                "Can only call functions and classes.".to_string(),
+            )),
+        }
+    }
+
+    fn call<C: LoxCallable>(
+        &mut self,
+        callee: C,
+        name: String,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, LoxError> {
+        if let Some(suspender) = self.suspender {
+            suspender.check_stack()?;
+        }
+
+        if arguments.len() != callee.arity() {
+            return Err(LoxError::RuntimeError(format!(
+                "'{}' expected {} arguments but got {}.",
+                name,
+                callee.arity(),
+                arguments.len()
+            )));
+        }
+
+        callee.call(self, arguments)
+    }
+
+    fn assign_variable(
+        &mut self,
+        name: Token,
+        expr: &dyn Expr,
+        value: LoxValue,
+    ) -> Result<(), LoxError> {
+        let distance = self.locals.borrow().get(&expr.id()).copied();
+        if let Some(dist) = distance {
+            let mut env_ref = self.environment.borrow_mut();
+            env_ref.assign_at(dist, name.lexeme(), value);
+            Ok(())
+        } else {
+            let result = self.globals.borrow_mut().assign(name.clone(), value);
+            result.map_err(|error| self.locate(error, &name))
+        }
+    }
+
+    fn subscript(&self, object: &LoxValue, index: &LoxValue) -> Result<LoxValue, LoxError> {
+        match object {
+            LoxValue::List(list) => lox_list::get(list, index),
+            LoxValue::Map(map) => lox_map::get(map, index),
+            LoxValue::String(string) => lox_string::get(string, index),
+            LoxValue::Tuple(values) => {
+                lox_list::position(index, values.len()).map(|position| values[position].clone())
+            }
+            _ => Err(LoxError::RuntimeError(
+                "Only lists, tuples, maps and strings can be indexed.".to_string(),
+            )),
+        }
+    }
+
+    fn subscript_set(
+        &self,
+        object: &LoxValue,
+        index: LoxValue,
+        value: LoxValue,
+    ) -> Result<(), LoxError> {
+        match object {
+            LoxValue::List(list) => lox_list::set(list, &index, value),
+            LoxValue::Map(map) => map.borrow_mut().set(index, value),
+            _ => Err(LoxError::RuntimeError(
+                "Only lists and maps can be indexed.".to_string(),
+            )),
+        }
+    }
+
+    fn destructure(
+        &mut self,
+        pattern: &Pattern,
+        value: LoxValue,
+        binding: Binding,
+    ) -> Result<(), LoxError> {
+        match pattern {
+            Pattern::Target(target) | Pattern::Rest(target) => self.bind(target, value, binding),
+            Pattern::Sequence(bracket, patterns) => {
+                let values = match &value {
+                    LoxValue::Tuple(values) => values.to_vec(),
+                    LoxValue::List(list) => list.borrow().clone(),
+                    _ => {
+                        let message = format!("Can't destructure {} by position.", value);
+                        return Err(self.locate(LoxError::RuntimeError(message), bracket));
+                    }
+                };
+
+                let rest = patterns
+                    .iter()
+                    .position(|pattern| matches!(pattern, Pattern::Rest(_)));
+                let required = patterns.len() - usize::from(rest.is_some());
+                if values.len() < required || (rest.is_none() && values.len() > required) {
+                    let expected = match rest {
+                        Some(_) => format!("at least {}", required),
+                        None => required.to_string(),
+                    };
+                    let message = format!(
+                        "Expected {} values to unpack but got {}.",
+                        expected,
+                        values.len()
+                    );
+                    return Err(self.locate(LoxError::RuntimeError(message), bracket));
+                }
+
+                let extra = values.len() - required;
+                let mut values = values.into_iter();
+                for (index, pattern) in patterns.iter().enumerate() {
+                    let value = if Some(index) == rest {
+                        lox_list::new(values.by_ref().take(extra).collect())
+                    } else {
+                        values.next().unwrap_or(LoxValue::Nil)
+                    };
+                    self.destructure(pattern, value, binding)?;
+                }
+                Ok(())
+            }
+            Pattern::Fields(brace, names) => {
+                for name in names {
+                    let field = match &value {
+                        LoxValue::Map(map) => lox_map::get(map, &LoxValue::String(name.lexeme())),
+                        LoxValue::Instance(instance) => LoxInstance::get(instance, name.clone()),
+                        _ => {
+                            let message = format!("Can't destructure {} by name.", value);
+                            return Err(self.locate(LoxError::RuntimeError(message), brace));
+                        }
+                    };
+                    let field = field.map_err(|error| self.locate(error, name))?;
+                    let target: BoxedExpr = Variable::new(name.clone());
+                    self.bind(&target, field, binding)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn bind(
+        &mut self,
+        target: &BoxedExpr,
+        value: LoxValue,
+        binding: Binding,
+    ) -> Result<(), LoxError> {
+        if let Some(variable) = target.as_any().downcast_ref::<Variable>() {
+            match binding {
+                Binding::Var => self.declare(&variable.name(), value, false)?,
+                Binding::Const => self.declare(&variable.name(), value, true)?,
+                Binding::Assign => self.assign_variable(variable.name(), variable, value)?,
+            }
+            Ok(())
+        } else if let Some(get) = target.as_any().downcast_ref::<Get>() {
+            match self.evaluate(get.object())? {
+                LoxValue::Instance(instance) => {
+                    instance.borrow_mut().set(get.name().lexeme(), value);
+                    Ok(())
+                }
+                _ => Err(self.locate(
+                    LoxError::RuntimeError("Only instances have fields.".to_string()),
+                    &get.name(),
+                )),
+            }
+        } else if let Some(subscript) = target.as_any().downcast_ref::<Subscript>() {
+            let object = self.evaluate(subscript.object())?;
+            let index = self.evaluate(subscript.index())?;
+            self.subscript_set(&object, index, value)
+                .map_err(|error| self.locate(error, &subscript.bracket()))
+        } else {
+            Err(LoxError::RuntimeError(
+                "Invalid assignment target.".to_string(),
+            ))
+        }
+    }
+
+    // Applies the arithmetic behind `+=`, `++` and friends to a target's current value
+    fn updated(&mut self, current: &LoxValue, expr: &Update) -> Result<LoxValue, LoxError> {
+        let value = self.evaluate(expr.value())?;
+        let operator = expr.operator();
+        let current = current.clone();
+
+        let result = match operator.token_type() {
+            TokenType::PlusEqual | TokenType::PlusPlus => current + value,
+            TokenType::MinusEqual | TokenType::MinusMinus => current - value,
+            TokenType::StarEqual => current * value,
+            TokenType::SlashEqual => current / value,
+            TokenType::PercentEqual => current % value,
+            _ => Ok(current),
+        };
+        result.map_err(|error| self.locate(error, &operator))
+    }
+
+    fn compare<F>(&self, left: &LoxValue, right: &LoxValue, test: F) -> Result<LoxValue, LoxError>
+    where
+        F: Fn(Ordering) -> bool,
+    {
+        match left.partial_cmp(right) {
+            Some(ordering) => Ok(LoxValue::Boolean(test(ordering))),
+            None => Err(LoxError::RuntimeError(format!(
+                "Can't compare {} with {}",
+                left, right
+            ))),
+        }
+    }
+
     fn is_truthy(&self, result: LoxValue) -> LoxValue {
         match result {
             LoxValue::Nil => LoxValue::Boolean(false),
             LoxValue::Number(_) => LoxValue::Boolean(true),
+            LoxValue::Integer(_) => LoxValue::Boolean(true),
+            LoxValue::BigInteger(_) => LoxValue::Boolean(true),
+            LoxValue::Decimal(_) => LoxValue::Boolean(true),
             LoxValue::String(_) => LoxValue::Boolean(true),
             LoxValue::Boolean(value) => LoxValue::Boolean(value),
             LoxValue::Function(_) => LoxValue::Boolean(true),
+            LoxValue::NativeFunction(_) => LoxValue::Boolean(true),
+            LoxValue::Class(_) => LoxValue::Boolean(true),
+            LoxValue::Instance(_) => LoxValue::Boolean(true),
+            LoxValue::List(_) => LoxValue::Boolean(true),
+            LoxValue::Tuple(_) => LoxValue::Boolean(true),
+            LoxValue::Map(_) => LoxValue::Boolean(true),
+            LoxValue::Range(_) => LoxValue::Boolean(true),
+            LoxValue::Generator(_) => LoxValue::Boolean(true),
         }
     }
 
@@ -261,17 +967,20 @@ impl Interpreter {
         &mut self,
         statements: Vec<Box<dyn Stmt>>,
         environment: Rc<RefCell<Environment>>,
-    ) -> Option<LoxValue> {
+    ) -> StmtResult {
         let previous = Rc::clone(&self.environment);
-        let mut value = None;
+        let mut value = Ok(None);
 
         self.environment = environment;
 
         for statement in statements {
-            if let Some(return_value) = self.execute(statement) {
-                value = Some(return_value);
-                break;
-            };
+            match self.execute(statement) {
+                Ok(None) => {}
+                result => {
+                    value = result;
+                    break;
+                }
+            }
         }
 
         self.environment = previous;
@@ -287,7 +996,7 @@ mod tests {
 
     #[test]
     fn it_evaluates_numeric_literals() {
-        let expr = Literal::new(LoxValue::Number(5.0f32));
+        let expr = Literal::new(LoxValue::Number(5.0));
         let mut interpreter = Interpreter::new();
         assert_eq!(interpreter.evaluate(expr).unwrap(), LoxValue::Number(5.0));
     }
@@ -305,10 +1014,379 @@ mod tests {
     #[test]
     fn it_evaluates_unary_minus_operators() {
         let expr = Unary::new(
-            Token::new("-".to_string(), TokenType::Minus),
+            Token::new("-".to_string(), TokenType::Minus, 1),
             Literal::new(LoxValue::Number(5.0)),
         );
         let mut interpreter = Interpreter::new();
         assert_eq!(interpreter.evaluate(expr).unwrap(), LoxValue::Number(-5.0))
     }
+
+    #[test]
+    fn it_locates_runtime_errors_raised_by_operators() {
+        let expr = Binary::new(
+            Literal::new(LoxValue::Number(1.0)),
+            Token::new("+".to_string(), TokenType::Plus, 3),
+            Literal::new(LoxValue::Nil),
+        );
+        let mut interpreter = Interpreter::new();
+        match interpreter.evaluate(expr) {
+            Err(LoxError::Exception(_, line)) => assert_eq!(line, 3),
+            result => panic!("Expected an exception but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn it_rejects_calls_with_the_wrong_number_of_arguments() {
+        let expr = Call::new(
+            Variable::new(Token::new("clock".to_string(), TokenType::Identifier, 1)),
+            Token::new(")".to_string(), TokenType::RightParen, 2),
+            vec![Literal::new(LoxValue::Number(1.0))],
+            false,
+        );
+        let mut interpreter = Interpreter::new();
+        match interpreter.evaluate(expr) {
+            Err(error) => assert_eq!(
+                format!("{}", error),
+                "[line 2] Error: 'clock' expected 0 arguments but got 1."
+            ),
+            result => panic!("Expected an arity error but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn it_indexes_lists_from_either_end() {
+        let bracket = Token::new("[".to_string(), TokenType::LeftBracket, 4);
+        let list = List::new(
+            bracket.clone(),
+            vec![
+                Literal::new(LoxValue::Number(1.0)),
+                Literal::new(LoxValue::Number(2.0)),
+            ],
+        );
+        let mut interpreter = Interpreter::new();
+
+        let last = Subscript::new(
+            list.clone(),
+            bracket.clone(),
+            Literal::new(LoxValue::Number(-1.0)),
+        );
+        assert_eq!(interpreter.evaluate(last).unwrap(), LoxValue::Number(2.0));
+
+        let missing = Subscript::new(list, bracket, Literal::new(LoxValue::Number(2.0)));
+        match interpreter.evaluate(missing) {
+            Err(error) => assert_eq!(
+                format!("{}", error),
+                "[line 4] Error: Index 2 is out of range for length 2."
+            ),
+            result => panic!("Expected an index error but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn it_keeps_map_entries_in_insertion_order() {
+        let brace = Token::new("{".to_string(), TokenType::LeftBrace, 1);
+        let entry = |key: &str, value: i64| -> (BoxedExpr, BoxedExpr) {
+            (
+                Literal::new(LoxValue::String(key.to_string())),
+                Literal::new(LoxValue::Integer(value)),
+            )
+        };
+        let expr = Map::new(brace, vec![entry("b", 1), entry("a", 2), entry("b", 3)]);
+        let mut interpreter = Interpreter::new();
+        let map = interpreter.evaluate(expr).unwrap();
+        assert_eq!(format!("{}", map), "{b: 3, a: 2}");
+    }
+
+    #[test]
+    fn it_keeps_integer_arithmetic_exact_past_i64() {
+        let plus = Token::new("+".to_string(), TokenType::Plus, 1);
+        let mut interpreter = Interpreter::new();
+
+        let sum = Binary::new(
+            Literal::new(LoxValue::Integer(16_777_217)),
+            plus.clone(),
+            Literal::new(LoxValue::Integer(1)),
+        );
+        assert_eq!(
+            interpreter.evaluate(sum).unwrap(),
+            LoxValue::Integer(16_777_218)
+        );
+
+        let overflow = Binary::new(
+            Literal::new(LoxValue::Integer(i64::MAX)),
+            plus,
+            Literal::new(LoxValue::Integer(1)),
+        );
+        assert_eq!(
+            format!("{}", interpreter.evaluate(overflow).unwrap()),
+            "9223372036854775808"
+        );
+    }
+
+    #[test]
+    fn it_compares_integers_with_floats_exactly() {
+        let integer = LoxValue::Integer(9_007_199_254_740_993);
+        let float = LoxValue::Number(9_007_199_254_740_992.0);
+        let mut interpreter = Interpreter::new();
+
+        let greater = Binary::new(
+            Literal::new(integer.clone()),
+            Token::new(">".to_string(), TokenType::Greater, 1),
+            Literal::new(float.clone()),
+        );
+        assert_eq!(
+            interpreter.evaluate(greater).unwrap(),
+            LoxValue::Boolean(true)
+        );
+
+        let equal = Binary::new(
+            Literal::new(integer),
+            Token::new("==".to_string(), TokenType::EqualEqual, 1),
+            Literal::new(float),
+        );
+        assert_eq!(
+            interpreter.evaluate(equal).unwrap(),
+            LoxValue::Boolean(false)
+        );
+    }
+
+    #[test]
+    fn it_adds_decimals_exactly() {
+        let decimal = |text: &str| Literal::new(LoxValue::Decimal(text.parse().unwrap()));
+        let expr = Binary::new(
+            decimal("0.1"),
+            Token::new("+".to_string(), TokenType::Plus, 1),
+            decimal("0.2"),
+        );
+        let mut interpreter = Interpreter::new();
+        assert_eq!(
+            interpreter.evaluate(expr).unwrap(),
+            LoxValue::Decimal("0.3".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn it_indexes_strings_by_character() {
+        let expr = Subscript::new(
+            Literal::new(LoxValue::String("naïve".to_string())),
+            Token::new("[".to_string(), TokenType::LeftBracket, 1),
+            Literal::new(LoxValue::Integer(2)),
+        );
+        let mut interpreter = Interpreter::new();
+        assert_eq!(
+            interpreter.evaluate(expr).unwrap(),
+            LoxValue::String("ï".to_string())
+        );
+    }
+
+    #[test]
+    fn it_rejects_bitwise_operators_on_floats() {
+        let expr = Binary::new(
+            Literal::new(LoxValue::Number(1.5)),
+            Token::new("&".to_string(), TokenType::Ampersand, 4),
+            Literal::new(LoxValue::Integer(1)),
+        );
+        let mut interpreter = Interpreter::new();
+        assert!(interpreter.evaluate(expr).is_err());
+    }
+
+    #[test]
+    fn it_returns_the_old_value_from_a_postfix_increment() {
+        let name = Token::new("count".to_string(), TokenType::Identifier, 1);
+        let mut interpreter = Interpreter::new();
+        interpreter
+            .environment()
+            .borrow_mut()
+            .define(name.lexeme(), LoxValue::Integer(41));
+
+        let expr = Update::new(
+            Variable::new(name.clone()),
+            Token::new("++".to_string(), TokenType::PlusPlus, 1),
+            Literal::new(LoxValue::Integer(1)),
+            false,
+        );
+        assert_eq!(interpreter.evaluate(expr).unwrap(), LoxValue::Integer(41));
+        assert_eq!(
+            interpreter.evaluate(Variable::new(name)).unwrap(),
+            LoxValue::Integer(42)
+        );
+    }
+
+    #[test]
+    fn it_short_circuits_the_rest_of_an_optional_chain() {
+        let name = |text: &str| Token::new(text.to_string(), TokenType::Identifier, 1);
+        let expr = OptionalChain::new(Get::new(
+            Get::new(Literal::new(LoxValue::Nil), name("next"), true),
+            name("value"),
+            false,
+        ));
+        let mut interpreter = Interpreter::new();
+        assert_eq!(interpreter.evaluate(expr).unwrap(), LoxValue::Nil);
+    }
+
+    #[test]
+    fn it_rejects_assignment_to_a_global_constant() {
+        let name = Token::new("limit".to_string(), TokenType::Identifier, 5);
+        let mut interpreter = Interpreter::new();
+        interpreter
+            .environment()
+            .borrow_mut()
+            .define_constant(name.lexeme(), LoxValue::Integer(3));
+
+        let expr = Assign::new(name.clone(), Literal::new(LoxValue::Integer(4)));
+        match interpreter.evaluate(expr) {
+            Err(LoxError::Exception(_, line)) => assert_eq!(line, 5),
+            result => panic!("Expected an exception but got {:?}", result),
+        }
+        assert_eq!(
+            interpreter.evaluate(Variable::new(name)).unwrap(),
+            LoxValue::Integer(3)
+        );
+    }
+
+    #[test]
+    fn it_rejects_redeclaring_a_global_constant() {
+        let name = Token::new("limit".to_string(), TokenType::Identifier, 7);
+        let mut interpreter = Interpreter::new();
+        interpreter
+            .environment()
+            .borrow_mut()
+            .define_constant(name.lexeme(), LoxValue::Integer(3));
+
+        let stmt = Var::new(
+            name.clone(),
+            Some(Literal::new(LoxValue::Integer(4))),
+            false,
+        );
+        match interpreter.execute(stmt) {
+            Err(LoxError::Exception(_, line)) => assert_eq!(line, 7),
+            result => panic!("Expected an exception but got {:?}", result),
+        }
+        assert_eq!(
+            interpreter.evaluate(Variable::new(name)).unwrap(),
+            LoxValue::Integer(3)
+        );
+    }
+
+    #[test]
+    fn it_swaps_variables_with_a_tuple_assignment() {
+        let a = Token::new("a".to_string(), TokenType::Identifier, 1);
+        let b = Token::new("b".to_string(), TokenType::Identifier, 1);
+        let paren = Token::new("(".to_string(), TokenType::LeftParen, 1);
+        let mut interpreter = Interpreter::new();
+        interpreter
+            .environment()
+            .borrow_mut()
+            .define(a.lexeme(), LoxValue::Integer(1));
+        interpreter
+            .environment()
+            .borrow_mut()
+            .define(b.lexeme(), LoxValue::Integer(2));
+
+        let pattern = Pattern::Sequence(
+            paren.clone(),
+            vec![
+                Pattern::Target(Variable::new(a.clone())),
+                Pattern::Target(Variable::new(b.clone())),
+            ],
+        );
+        let value = Tuple::new(
+            paren,
+            vec![Variable::new(b.clone()), Variable::new(a.clone())],
+        );
+        interpreter
+            .evaluate(AssignPattern::new(pattern, value))
+            .unwrap();
+
+        assert_eq!(
+            interpreter.evaluate(Variable::new(a)).unwrap(),
+            LoxValue::Integer(2)
+        );
+        assert_eq!(
+            interpreter.evaluate(Variable::new(b)).unwrap(),
+            LoxValue::Integer(1)
+        );
+    }
+
+    #[test]
+    fn it_steps_through_an_inclusive_range() {
+        let expr = Range::new(
+            Literal::new(LoxValue::Integer(0)),
+            Token::new("..=".to_string(), TokenType::DotDotEqual, 1),
+            Literal::new(LoxValue::Integer(10)),
+            Some(Literal::new(LoxValue::Integer(5))),
+        );
+        let mut interpreter = Interpreter::new();
+        let range = interpreter.evaluate(expr).unwrap();
+
+        let mut iterator = LoxIterator::new(&mut interpreter, range).unwrap();
+        let mut values = Vec::new();
+        while let Some(value) = iterator.next(&mut interpreter).unwrap() {
+            values.push(value);
+        }
+        assert_eq!(
+            values,
+            vec![
+                LoxValue::Integer(0),
+                LoxValue::Integer(5),
+                LoxValue::Integer(10)
+            ]
+        );
+    }
+
+    #[test]
+    fn it_suspends_a_generator_between_yields() {
+        let keyword = Token::new("yield".to_string(), TokenType::Yield, 1);
+        let declaration = Function::new(
+            Token::new("pair".to_string(), TokenType::Identifier, 1),
+            Vec::new(),
+            vec![
+                Yield::new(keyword.clone(), Some(Literal::new(LoxValue::Integer(1)))),
+                Yield::new(keyword, Some(Literal::new(LoxValue::Integer(2)))),
+            ],
+            true,
+        );
+        let mut interpreter = Interpreter::new();
+        let function = LoxFunction::new(*declaration, interpreter.environment(), false);
+
+        let generator = match function.call(&mut interpreter, Vec::new()).unwrap() {
+            LoxValue::Generator(generator) => generator,
+            value => panic!("Expected a generator but got {:?}", value),
+        };
+        assert_eq!(generator.next().unwrap(), Some(LoxValue::Integer(1)));
+        assert!(generator.has_next().unwrap());
+        assert_eq!(generator.next().unwrap(), Some(LoxValue::Integer(2)));
+        assert_eq!(generator.next().unwrap(), None);
+    }
+
+    #[test]
+    fn it_frees_a_suspended_generator_held_in_a_local() {
+        let keyword = Token::new("yield".to_string(), TokenType::Yield, 1);
+        let declaration = Function::new(
+            Token::new("forever".to_string(), TokenType::Identifier, 1),
+            Vec::new(),
+            vec![
+                Yield::new(keyword.clone(), Some(Literal::new(LoxValue::Integer(1)))),
+                Yield::new(keyword, Some(Literal::new(LoxValue::Integer(2)))),
+            ],
+            true,
+        );
+        let mut interpreter = Interpreter::new();
+        let function = LoxFunction::new(*declaration, interpreter.environment(), false);
+
+        let local = Rc::new(RefCell::new(Environment::new_with(
+            interpreter.environment(),
+        )));
+        interpreter.environment = Rc::clone(&local);
+        let generator = function.call(&mut interpreter, Vec::new()).unwrap();
+        if let LoxValue::Generator(generator) = &generator {
+            assert_eq!(generator.next().unwrap(), Some(LoxValue::Integer(1)));
+        }
+        local.borrow_mut().define("it".to_string(), generator);
+        interpreter.environment = Rc::clone(&interpreter.globals);
+
+        let weak = Rc::downgrade(&local);
+        drop(local);
+        assert!(weak.upgrade().is_none());
+    }
 }
@@ -12,14 +12,26 @@ use std::io::BufReader;
 use rustyline::error::ReadlineError;
 use rustyline::Editor;
 
+mod builtins;
 mod environment;
 mod expr;
 mod interpreter;
 mod lox_callable;
+mod lox_class;
+mod lox_decimal;
 mod lox_error;
 mod lox_function;
+mod lox_generator;
+mod lox_instance;
+mod lox_iterator;
+mod lox_list;
+mod lox_map;
+mod lox_range;
+mod lox_string;
 mod lox_value;
+mod native_function;
 mod parser;
+mod pattern;
 mod resolver;
 mod scanner;
 mod stmt;
@@ -68,11 +80,21 @@ fn run(source: &str) -> io::Result<()> {
     match scanner.scan() {
         Ok(tokens) => {
             let mut parser = Parser::new(tokens.to_vec());
+            let statements = match parser.parse() {
+                Ok(statements) => statements,
+                Err(errors) => {
+                    for e in errors {
+                        error(e.line(), &e.to_string());
+                    }
+                    return Ok(());
+                }
+            };
             let mut interpreter = Interpreter::new();
-            let statements = parser.parse();
             let mut resolver = Resolver::new(&mut interpreter);
-            resolver.resolve(&statements).ok();
-            interpreter.interpret(statements)
+            match resolver.resolve(&statements) {
+                Ok(()) => interpreter.interpret(statements),
+                Err(e) => eprintln!("{}", e),
+            }
         }
         Err(e) => error(e.line(), e.description()),
     }
@@ -3,7 +3,7 @@ use crate::lox_error::LoxError;
 use crate::lox_value::LoxValue;
 
 pub trait LoxCallable {
-    fn arity();
+    fn arity(&self) -> usize;
     fn call(
         self,
         interpreter: &mut Interpreter,
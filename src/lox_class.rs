@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use crate::interpreter::Interpreter;
+use crate::lox_callable::LoxCallable;
+use crate::lox_error::LoxError;
+use crate::lox_function::LoxFunction;
+use crate::lox_instance::LoxInstance;
+use crate::lox_value::LoxValue;
+
+#[derive(Debug, Clone)]
+pub struct LoxClass {
+    name: String,
+    superclass: Option<Box<LoxClass>>,
+    methods: Rc<HashMap<String, LoxFunction>>,
+    doc: Option<Rc<String>>,
+}
+
+impl LoxCallable for LoxClass {
+    fn arity(&self) -> usize {
+        match self.find_method("init") {
+            Some(initializer) => initializer.arity(),
+            None => 0,
+        }
+    }
+
+    fn call(
+        self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, LoxError> {
+        let initializer = self.find_method("init");
+        let instance = LoxInstance::new(self);
+        if let Some(initializer) = initializer {
+            initializer
+                .bind(instance.clone())
+                .call(interpreter, arguments)?;
+        }
+        Ok(instance)
+    }
+}
+
+impl PartialEq for LoxClass {
+    fn eq(&self, other: &LoxClass) -> bool {
+        Rc::ptr_eq(&self.methods, &other.methods)
+    }
+}
+
+impl Hash for LoxClass {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Rc::as_ptr(&self.methods).hash(state);
+    }
+}
+
+impl LoxClass {
+    pub fn new(
+        name: String,
+        superclass: Option<LoxClass>,
+        methods: HashMap<String, LoxFunction>,
+    ) -> LoxClass {
+        LoxClass {
+            name,
+            superclass: superclass.map(Box::new),
+            methods: Rc::new(methods),
+            doc: None,
+        }
+    }
+
+    pub fn with_doc(self, doc: Option<String>) -> LoxClass {
+        LoxClass {
+            doc: doc.map(Rc::new),
+            ..self
+        }
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn doc(&self) -> Option<String> {
+        self.doc.as_ref().map(|doc| doc.to_string())
+    }
+
+    pub fn find_method(&self, name: &str) -> Option<LoxFunction> {
+        match self.methods.get(name) {
+            Some(method) => Some(method.clone()),
+            None => match &self.superclass {
+                Some(superclass) => superclass.find_method(name),
+                None => None,
+            },
+        }
+    }
+}
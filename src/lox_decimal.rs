@@ -0,0 +1,104 @@
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::lox_error::LoxError;
+use crate::lox_value::LoxValue;
+use crate::native_function::NativeFunction;
+use crate::token::Token;
+
+pub fn parse(text: &str) -> Result<Decimal, LoxError> {
+    Decimal::from_str_exact(text.trim())
+        .map_err(|_| LoxError::RuntimeError(format!("Can't convert '{}' to a decimal.", text)))
+}
+
+// Integers convert exactly, and floats go through their printed form so 0.1 stays 0.1
+pub fn from_value(value: &LoxValue) -> Result<Decimal, LoxError> {
+    match value {
+        LoxValue::Decimal(value) => Ok(*value),
+        LoxValue::String(text) => parse(text),
+        LoxValue::Integer(_) | LoxValue::BigInteger(_) | LoxValue::Number(_) => {
+            parse(&value.to_string())
+        }
+        _ => Err(LoxError::RuntimeError(format!(
+            "Can't convert {} to a decimal.",
+            value
+        ))),
+    }
+}
+
+pub fn rounding(mode: &LoxValue) -> Result<RoundingStrategy, LoxError> {
+    let strategy = match mode {
+        LoxValue::String(mode) => match mode.as_str() {
+            "half_even" => Some(RoundingStrategy::MidpointNearestEven),
+            "half_up" => Some(RoundingStrategy::MidpointAwayFromZero),
+            "half_down" => Some(RoundingStrategy::MidpointTowardZero),
+            "up" => Some(RoundingStrategy::AwayFromZero),
+            "down" => Some(RoundingStrategy::ToZero),
+            "ceiling" => Some(RoundingStrategy::ToPositiveInfinity),
+            "floor" => Some(RoundingStrategy::ToNegativeInfinity),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    strategy.ok_or_else(|| {
+        LoxError::RuntimeError(format!(
+            "Unknown rounding mode {}, expected one of half_even, half_up, half_down, up, down, ceiling or floor.",
+            mode
+        ))
+    })
+}
+
+fn scale(scale: &LoxValue) -> Result<u32, LoxError> {
+    match scale.as_integer() {
+        Some(scale) if (0..=28).contains(&scale) => Ok(scale as u32),
+        _ => Err(LoxError::RuntimeError(format!(
+            "Scale must be a whole number between 0 and 28, got {}.",
+            scale
+        ))),
+    }
+}
+
+pub fn round(value: Decimal, places: &LoxValue, mode: &LoxValue) -> Result<LoxValue, LoxError> {
+    let places = scale(places)?;
+    let mut rounded = value.round_dp_with_strategy(places, rounding(mode)?);
+    rounded.rescale(places);
+    Ok(LoxValue::Decimal(rounded))
+}
+
+pub fn divide(
+    value: Decimal,
+    divisor: &LoxValue,
+    places: &LoxValue,
+    mode: &LoxValue,
+) -> Result<LoxValue, LoxError> {
+    let divisor = from_value(divisor)?;
+    if divisor.is_zero() {
+        return Err(LoxError::RuntimeError("Division by zero.".to_string()));
+    }
+
+    match value.checked_div(divisor) {
+        Some(quotient) => round(quotient, places, mode),
+        None => Err(LoxError::RuntimeError("Decimal overflow.".to_string())),
+    }
+}
+
+pub fn method(value: Decimal, name: &Token) -> Result<LoxValue, LoxError> {
+    let method = match name.lexeme().as_str() {
+        "divide" => NativeFunction::new("divide", 3, move |_, arguments| {
+            divide(value, &arguments[0], &arguments[1], &arguments[2])
+        }),
+        "round" => NativeFunction::new("round", 2, move |_, arguments| {
+            round(value, &arguments[0], &arguments[1])
+        }),
+        "scale" => NativeFunction::new("scale", 0, move |_, _| {
+            Ok(LoxValue::Integer(i64::from(value.scale())))
+        }),
+        _ => {
+            return Err(LoxError::RuntimeError(format!(
+                "Undefined decimal method '{}'.",
+                name.lexeme()
+            )))
+        }
+    };
+    Ok(LoxValue::NativeFunction(method))
+}
@@ -1,9 +1,15 @@
 use std::fmt::Display;
 
+use crate::lox_value::LoxValue;
+
 #[derive(Debug)]
 pub enum LoxError {
     RuntimeError(String),
     BindingError(String, String),
+    Exception(LoxValue, u32),
+    Break,
+    Continue,
+    ShortCircuit,
 }
 
 impl Display for LoxError {
@@ -13,6 +19,28 @@ impl Display for LoxError {
             LoxError::BindingError(token, message) => {
                 write!(f, "BindingError for {}: {}", token, message)
             }
+            LoxError::Exception(LoxValue::Instance(instance), line) => {
+                let instance = instance.borrow();
+                match instance.field("message") {
+                    Some(message) => write!(
+                        f,
+                        "[line {}] {}: {}",
+                        line,
+                        instance.class().name(),
+                        message
+                    ),
+                    None => write!(
+                        f,
+                        "[line {}] Uncaught {} instance",
+                        line,
+                        instance.class().name()
+                    ),
+                }
+            }
+            LoxError::Exception(value, line) => write!(f, "[line {}] Uncaught {}", line, value),
+            LoxError::Break => write!(f, "'break' used outside of a loop"),
+            LoxError::Continue => write!(f, "'continue' used outside of a loop"),
+            LoxError::ShortCircuit => write!(f, "'?.' used outside of an optional chain"),
         }
     }
 }
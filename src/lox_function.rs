@@ -1,29 +1,36 @@
 use std::cell::RefCell;
 use std::cmp::PartialEq;
+use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
 use crate::environment::Environment;
 use crate::interpreter::Interpreter;
 use crate::lox_callable::LoxCallable;
 use crate::lox_error::LoxError;
+use crate::lox_generator::LoxGenerator;
 use crate::lox_value::LoxValue;
 use crate::stmt::Function;
 
 #[derive(Debug, Clone)]
 pub struct LoxFunction {
-    declaration: Function,
+    declaration: Rc<Function>,
     closure: Rc<RefCell<Environment>>,
+    is_initializer: bool,
 }
 
 impl LoxCallable for LoxFunction {
-    fn arity() {}
+    fn arity(&self) -> usize {
+        self.declaration.params().len()
+    }
 
     fn call(
         self,
         interpreter: &mut Interpreter,
         arguments: Vec<LoxValue>,
     ) -> Result<LoxValue, LoxError> {
-        let environment = Rc::new(RefCell::new(Environment::new_with(self.closure)));
+        let environment = Rc::new(RefCell::new(Environment::new_with(Rc::clone(
+            &self.closure,
+        ))));
 
         for (i, param) in self.declaration.params().iter().enumerate() {
             let name = param.lexeme();
@@ -32,7 +39,22 @@ impl LoxCallable for LoxFunction {
             env_ref.define(name, argument.clone());
         }
 
-        match interpreter.execute_block(self.declaration.body(), environment) {
+        if self.declaration.generator() {
+            return LoxGenerator::new(
+                self.name(),
+                interpreter,
+                self.declaration.body(),
+                environment,
+            );
+        }
+
+        let value = interpreter.execute_block(self.declaration.body(), environment)?;
+
+        if self.is_initializer {
+            return Ok(self.closure.borrow_mut().get_at(0, "this".to_string()));
+        }
+
+        match value {
             Some(value) => Ok(value),
             None => Ok(LoxValue::Nil),
         }
@@ -40,20 +62,46 @@ impl LoxCallable for LoxFunction {
 }
 
 impl PartialEq for LoxFunction {
-    fn eq(&self, _other: &LoxFunction) -> bool {
-        false
+    fn eq(&self, other: &LoxFunction) -> bool {
+        self.declaration.id() == other.declaration.id() && Rc::ptr_eq(&self.closure, &other.closure)
+    }
+}
+
+impl Hash for LoxFunction {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.declaration.id().hash(state);
+        Rc::as_ptr(&self.closure).hash(state);
     }
 }
 
 impl LoxFunction {
-    pub fn new(declaration: Function, closure: Rc<RefCell<Environment>>) -> LoxFunction {
+    pub fn new(
+        declaration: Function,
+        closure: Rc<RefCell<Environment>>,
+        is_initializer: bool,
+    ) -> LoxFunction {
         LoxFunction {
-            declaration,
+            declaration: Rc::new(declaration),
             closure,
+            is_initializer,
+        }
+    }
+
+    pub fn bind(&self, instance: LoxValue) -> LoxFunction {
+        let mut environment = Environment::new_with(Rc::clone(&self.closure));
+        environment.define("this".to_string(), instance);
+        LoxFunction {
+            declaration: Rc::clone(&self.declaration),
+            closure: Rc::new(RefCell::new(environment)),
+            is_initializer: self.is_initializer,
         }
     }
 
     pub fn name(&self) -> String {
         self.declaration.name().lexeme()
     }
+
+    pub fn doc(&self) -> Option<String> {
+        self.declaration.doc()
+    }
 }
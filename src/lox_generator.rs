@@ -0,0 +1,161 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use corosensei::stack::{DefaultStack, Stack};
+use corosensei::{Coroutine, CoroutineResult, Yielder};
+
+use crate::environment::Environment;
+use crate::interpreter::Interpreter;
+use crate::lox_error::LoxError;
+use crate::lox_value::LoxValue;
+use crate::native_function::NativeFunction;
+use crate::stmt::BoxedStmt;
+use crate::token::Token;
+
+// Every live generator reserves a stack of its own, so they're kept much
+// smaller than the main thread's and recursion inside a generator can't go
+// as deep. Calls check what's left and raise an error before running out.
+const STACK_SIZE: usize = 1024 * 1024;
+// Room left free below the deepest call, comfortably more than one call needs
+const STACK_RESERVE: usize = 128 * 1024;
+
+type Body = Coroutine<(), LoxValue, Result<(), LoxError>, DefaultStack>;
+
+pub type GeneratorRef = Rc<LoxGenerator>;
+
+// Calling a function that contains `yield` returns one of these instead of
+// running the body. The body runs on a stack of its own, so a `yield` can
+// suspend it partway through and the next `next()` picks up where it left off.
+pub struct LoxGenerator {
+    name: String,
+    state: RefCell<State>,
+}
+
+struct State {
+    // None once the body has run to completion
+    body: Option<Body>,
+    // A value `hasNext()` ran ahead to, waiting to be returned by `next()`
+    peeked: Option<LoxValue>,
+}
+
+// Lets a `yield` statement suspend the coroutine running its generator's body
+#[derive(Clone, Copy)]
+pub struct Suspender {
+    yielder: *const Yielder<(), LoxValue>,
+    // Lowest address of the coroutine's stack, which grows down towards it
+    limit: usize,
+}
+
+impl Suspender {
+    pub fn suspend(self, value: LoxValue) {
+        // SAFETY: a suspender is only ever handed to the interpreter a
+        // generator's coroutine owns, from inside that coroutine, so the
+        // yielder it points at outlives every call made through it.
+        unsafe { (*self.yielder).suspend(value) }
+    }
+
+    pub fn check_stack(self) -> Result<(), LoxError> {
+        let marker = 0u8;
+        let position = &marker as *const u8 as usize;
+        if position < self.limit + STACK_RESERVE {
+            return Err(LoxError::RuntimeError(
+                "Stack overflow inside a generator.".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl LoxGenerator {
+    pub fn new(
+        name: String,
+        interpreter: &Interpreter,
+        statements: Vec<BoxedStmt>,
+        environment: Rc<RefCell<Environment>>,
+    ) -> Result<LoxValue, LoxError> {
+        let stack = DefaultStack::new(STACK_SIZE).map_err(|error| {
+            LoxError::RuntimeError(format!("Can't allocate a generator stack: {}", error))
+        })?;
+
+        let limit = stack.limit().get();
+        let interpreter = interpreter.for_generator();
+        let body = Coroutine::with_stack(stack, move |yielder: &Yielder<(), LoxValue>, ()| {
+            let mut interpreter = interpreter.with_suspender(Suspender { yielder, limit });
+            interpreter
+                .execute_block(statements, environment)
+                .map(|_| ())
+        });
+
+        Ok(LoxValue::Generator(Rc::new(LoxGenerator {
+            name,
+            state: RefCell::new(State {
+                body: Some(body),
+                peeked: None,
+            }),
+        })))
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    // Runs the body up to its next `yield`, or returns None once it has finished
+    pub fn next(&self) -> Result<Option<LoxValue>, LoxError> {
+        let mut state = self.state.try_borrow_mut().map_err(|_| {
+            LoxError::RuntimeError(format!("Generator '{}' is already running.", self.name))
+        })?;
+
+        if let Some(value) = state.peeked.take() {
+            return Ok(Some(value));
+        }
+
+        let result = match &mut state.body {
+            Some(body) => body.resume(()),
+            None => return Ok(None),
+        };
+        match result {
+            CoroutineResult::Yield(value) => Ok(Some(value)),
+            CoroutineResult::Return(result) => {
+                state.body = None;
+                result.map(|_| None)
+            }
+        }
+    }
+
+    pub fn has_next(&self) -> Result<bool, LoxError> {
+        let value = self.next()?;
+        let has_next = value.is_some();
+        self.state.borrow_mut().peeked = value;
+        Ok(has_next)
+    }
+}
+
+impl fmt::Debug for LoxGenerator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LoxGenerator({})", self.name)
+    }
+}
+
+pub fn method(generator: &GeneratorRef, name: &Token) -> Result<LoxValue, LoxError> {
+    let generator = Rc::clone(generator);
+    let method = match name.lexeme().as_str() {
+        "next" => NativeFunction::new("next", 0, move |_, _| match generator.next()? {
+            Some(value) => Ok(value),
+            None => Err(LoxError::RuntimeError(format!(
+                "Generator '{}' is exhausted.",
+                generator.name()
+            ))),
+        }),
+        "hasNext" => NativeFunction::new("hasNext", 0, move |_, _| {
+            Ok(LoxValue::Boolean(generator.has_next()?))
+        }),
+        _ => {
+            return Err(LoxError::RuntimeError(format!(
+                "Undefined generator method '{}'.",
+                name.lexeme()
+            )))
+        }
+    };
+    Ok(LoxValue::NativeFunction(method))
+}
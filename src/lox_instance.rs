@@ -0,0 +1,65 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ptr;
+use std::rc::Rc;
+
+use crate::lox_class::LoxClass;
+use crate::lox_error::LoxError;
+use crate::lox_value::LoxValue;
+use crate::token::Token;
+
+#[derive(Debug)]
+pub struct LoxInstance {
+    class: LoxClass,
+    fields: HashMap<String, LoxValue>,
+}
+
+impl PartialEq for LoxInstance {
+    fn eq(&self, other: &LoxInstance) -> bool {
+        ptr::eq(self, other)
+    }
+}
+
+impl LoxInstance {
+    pub fn new(class: LoxClass) -> LoxValue {
+        LoxValue::Instance(Rc::new(RefCell::new(LoxInstance {
+            class,
+            fields: HashMap::new(),
+        })))
+    }
+
+    pub fn class(&self) -> &LoxClass {
+        &self.class
+    }
+
+    pub fn get(instance: &Rc<RefCell<LoxInstance>>, name: Token) -> Result<LoxValue, LoxError> {
+        match LoxInstance::property(instance, &name.lexeme()) {
+            Some(value) => Ok(value),
+            None => Err(LoxError::RuntimeError(format!(
+                "Undefined property '{}'.",
+                name.lexeme()
+            ))),
+        }
+    }
+
+    // Fields shadow methods of the same name
+    pub fn property(instance: &Rc<RefCell<LoxInstance>>, name: &str) -> Option<LoxValue> {
+        let instance_ref = instance.borrow();
+        if let Some(value) = instance_ref.fields.get(name) {
+            return Some(value.clone());
+        }
+
+        instance_ref
+            .class
+            .find_method(name)
+            .map(|method| LoxValue::Function(method.bind(LoxValue::Instance(Rc::clone(instance)))))
+    }
+
+    pub fn field(&self, name: &str) -> Option<LoxValue> {
+        self.fields.get(name).cloned()
+    }
+
+    pub fn set(&mut self, name: String, value: LoxValue) {
+        self.fields.insert(name, value);
+    }
+}
@@ -0,0 +1,94 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::interpreter::Interpreter;
+use crate::lox_error::LoxError;
+use crate::lox_generator::GeneratorRef;
+use crate::lox_instance::LoxInstance;
+use crate::lox_list::ListRef;
+use crate::lox_map::MapRef;
+use crate::lox_range::LoxRange;
+use crate::lox_value::LoxValue;
+
+// The state behind a `for (x in iterable)` loop. Lists and maps are read by
+// position on every step, so values appended during the loop are still visited.
+pub enum LoxIterator {
+    List(ListRef, usize),
+    Tuple(Rc<Vec<LoxValue>>, usize),
+    Map(MapRef, usize),
+    String(Vec<char>, usize),
+    Range(LoxRange, usize),
+    Generator(GeneratorRef),
+    Object(Rc<RefCell<LoxInstance>>),
+}
+
+impl LoxIterator {
+    // Objects take part by returning an iterator or any other iterable from
+    // `iterator()`, or by being an iterator themselves: anything with
+    // `hasNext()` and `next()` methods
+    pub fn new(interpreter: &mut Interpreter, iterable: LoxValue) -> Result<LoxIterator, LoxError> {
+        match iterable {
+            LoxValue::List(list) => Ok(LoxIterator::List(list, 0)),
+            LoxValue::Tuple(values) => Ok(LoxIterator::Tuple(values, 0)),
+            LoxValue::Map(map) => Ok(LoxIterator::Map(map, 0)),
+            LoxValue::String(string) => Ok(LoxIterator::String(string.chars().collect(), 0)),
+            LoxValue::Range(range) => Ok(LoxIterator::Range(range, 0)),
+            LoxValue::Generator(generator) => Ok(LoxIterator::Generator(generator)),
+            LoxValue::Instance(instance) => match LoxInstance::property(&instance, "iterator") {
+                Some(method) => match interpreter.call_value(method, Vec::new())? {
+                    LoxValue::Instance(iterator) => Ok(LoxIterator::Object(iterator)),
+                    value => LoxIterator::new(interpreter, value.clone()).map_err(|_| {
+                        LoxError::RuntimeError(format!(
+                            "iterator() must return an iterator but returned {}.",
+                            value
+                        ))
+                    }),
+                },
+                None => Ok(LoxIterator::Object(instance)),
+            },
+            value => Err(LoxError::RuntimeError(format!(
+                "Can't iterate over {}.",
+                value
+            ))),
+        }
+    }
+
+    pub fn next(&mut self, interpreter: &mut Interpreter) -> Result<Option<LoxValue>, LoxError> {
+        let (value, index) = match self {
+            LoxIterator::List(list, index) => (list.borrow().get(*index).cloned(), index),
+            LoxIterator::Tuple(values, index) => (values.get(*index).cloned(), index),
+            LoxIterator::Map(map, index) => {
+                let entry = map.borrow().entries().get(*index).cloned();
+                let entry = entry.map(|(key, value)| LoxValue::Tuple(Rc::new(vec![key, value])));
+                (entry, index)
+            }
+            LoxIterator::String(chars, index) => {
+                let value = chars.get(*index).map(|c| LoxValue::String(c.to_string()));
+                (value, index)
+            }
+            LoxIterator::Range(range, index) => (range.get(*index).map(LoxValue::Integer), index),
+            LoxIterator::Generator(generator) => return generator.next(),
+            LoxIterator::Object(iterator) => {
+                let has_next = interpreter.call_value(method(iterator, "hasNext")?, Vec::new())?;
+                return match has_next {
+                    LoxValue::Nil | LoxValue::Boolean(false) => Ok(None),
+                    _ => interpreter
+                        .call_value(method(iterator, "next")?, Vec::new())
+                        .map(Some),
+                };
+            }
+        };
+        *index += 1;
+        Ok(value)
+    }
+}
+
+fn method(iterator: &Rc<RefCell<LoxInstance>>, name: &str) -> Result<LoxValue, LoxError> {
+    LoxInstance::property(iterator, name).ok_or_else(|| {
+        LoxError::RuntimeError(format!(
+            "{} instance is not iterable: it has no '{}' method.",
+            iterator.borrow().class().name(),
+            name
+        ))
+    })
+}
@@ -0,0 +1,115 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::lox_error::LoxError;
+use crate::lox_value::LoxValue;
+use crate::native_function::NativeFunction;
+use crate::token::Token;
+
+pub type ListRef = Rc<RefCell<Vec<LoxValue>>>;
+
+pub fn new(values: Vec<LoxValue>) -> LoxValue {
+    LoxValue::List(Rc::new(RefCell::new(values)))
+}
+
+// Converts a Lox index into a position, counting negative indices from the end
+pub fn position(index: &LoxValue, length: usize) -> Result<usize, LoxError> {
+    let offset = match index.as_integer() {
+        Some(offset) => offset,
+        None => {
+            return Err(LoxError::RuntimeError(format!(
+                "Index must be a whole number, got {}.",
+                index
+            )))
+        }
+    };
+
+    let position = if offset < 0 {
+        length as i64 + offset
+    } else {
+        offset
+    };
+
+    if position < 0 || position >= length as i64 {
+        return Err(LoxError::RuntimeError(format!(
+            "Index {} is out of range for length {}.",
+            offset, length
+        )));
+    }
+    Ok(position as usize)
+}
+
+// Like `position` but clamps to the bounds instead of failing, as slices do
+pub fn bound(index: Option<LoxValue>, length: usize, default: usize) -> Result<usize, LoxError> {
+    let offset = match index {
+        None => return Ok(default),
+        Some(value) if value.as_integer().is_some() => value.as_integer().unwrap(),
+        Some(value) => {
+            return Err(LoxError::RuntimeError(format!(
+                "Slice bounds must be whole numbers, got {}.",
+                value
+            )))
+        }
+    };
+
+    let position = if offset < 0 {
+        length as i64 + offset
+    } else {
+        offset
+    };
+    Ok(position.max(0).min(length as i64) as usize)
+}
+
+pub fn get(list: &ListRef, index: &LoxValue) -> Result<LoxValue, LoxError> {
+    let values = list.borrow();
+    let position = position(index, values.len())?;
+    Ok(values[position].clone())
+}
+
+pub fn set(list: &ListRef, index: &LoxValue, value: LoxValue) -> Result<(), LoxError> {
+    let mut values = list.borrow_mut();
+    let position = position(index, values.len())?;
+    values[position] = value;
+    Ok(())
+}
+
+pub fn slice(
+    list: &ListRef,
+    start: Option<LoxValue>,
+    end: Option<LoxValue>,
+) -> Result<LoxValue, LoxError> {
+    let values = list.borrow();
+    let start = bound(start, values.len(), 0)?;
+    let end = bound(end, values.len(), values.len())?;
+
+    if start >= end {
+        return Ok(new(Vec::new()));
+    }
+    Ok(new(values[start..end].to_vec()))
+}
+
+pub fn method(list: &ListRef, name: &Token) -> Result<LoxValue, LoxError> {
+    let list = Rc::clone(list);
+    let method = match name.lexeme().as_str() {
+        "len" => NativeFunction::new("len", 0, move |_, _| {
+            Ok(LoxValue::Integer(list.borrow().len() as i64))
+        }),
+        "push" => NativeFunction::new("push", 1, move |_, arguments| {
+            list.borrow_mut().extend(arguments);
+            Ok(LoxValue::Nil)
+        }),
+        "pop" => NativeFunction::new("pop", 0, move |_, _| match list.borrow_mut().pop() {
+            Some(value) => Ok(value),
+            None => Err(LoxError::RuntimeError(
+                "Cannot pop from an empty list.".to_string(),
+            )),
+        }),
+        _ => {
+            return Err(LoxError::RuntimeError(format!(
+                "Undefined list method '{}'.",
+                name.lexeme()
+            )))
+        }
+    };
+    Ok(LoxValue::NativeFunction(method))
+}
@@ -0,0 +1,156 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::lox_error::LoxError;
+use crate::lox_list;
+use crate::lox_value::LoxValue;
+use crate::native_function::NativeFunction;
+use crate::token::Token;
+
+pub type MapRef = Rc<RefCell<LoxMap>>;
+
+// Entries are kept in insertion order, with a hash index into them for lookups
+#[derive(Debug, Clone, Default)]
+pub struct LoxMap {
+    entries: Vec<(LoxValue, LoxValue)>,
+    indices: HashMap<LoxValue, usize>,
+}
+
+impl LoxMap {
+    pub fn new() -> LoxMap {
+        LoxMap::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get(&self, key: &LoxValue) -> Option<LoxValue> {
+        self.indices
+            .get(key)
+            .map(|index| self.entries[*index].1.clone())
+    }
+
+    pub fn has(&self, key: &LoxValue) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    pub fn set(&mut self, key: LoxValue, value: LoxValue) -> Result<(), LoxError> {
+        if !is_key(&key) {
+            return Err(LoxError::RuntimeError(format!(
+                "{} can't be used as a map key.",
+                key
+            )));
+        }
+
+        match self.indices.get(&key) {
+            Some(index) => self.entries[*index].1 = value,
+            None => {
+                self.indices.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn delete(&mut self, key: &LoxValue) -> Option<LoxValue> {
+        let index = self.indices.remove(key)?;
+        let (_, value) = self.entries.remove(index);
+        for position in self.indices.values_mut() {
+            if *position > index {
+                *position -= 1;
+            }
+        }
+        Some(value)
+    }
+
+    pub fn entries(&self) -> &[(LoxValue, LoxValue)] {
+        &self.entries
+    }
+}
+
+// Keys have to be immutable so their hash can't change while they're in a map,
+// and NaN is out because it never equals itself so could never be looked up
+fn is_key(key: &LoxValue) -> bool {
+    match key {
+        LoxValue::Number(value) => !value.is_nan(),
+        LoxValue::Nil
+        | LoxValue::Boolean(_)
+        | LoxValue::Integer(_)
+        | LoxValue::BigInteger(_)
+        | LoxValue::Decimal(_)
+        | LoxValue::String(_)
+        | LoxValue::Range(_) => true,
+        LoxValue::Tuple(values) => values.iter().all(is_key),
+        _ => false,
+    }
+}
+
+pub fn new(entries: Vec<(LoxValue, LoxValue)>) -> Result<LoxValue, LoxError> {
+    let mut map = LoxMap::new();
+    for (key, value) in entries {
+        map.set(key, value)?;
+    }
+    Ok(LoxValue::Map(Rc::new(RefCell::new(map))))
+}
+
+pub fn get(map: &MapRef, key: &LoxValue) -> Result<LoxValue, LoxError> {
+    match map.borrow().get(key) {
+        Some(value) => Ok(value),
+        None => Err(LoxError::RuntimeError(format!("Undefined key '{}'.", key))),
+    }
+}
+
+pub fn method(map: &MapRef, name: &Token) -> Result<LoxValue, LoxError> {
+    let map = Rc::clone(map);
+    let method = match name.lexeme().as_str() {
+        "len" => NativeFunction::new("len", 0, move |_, _| {
+            Ok(LoxValue::Integer(map.borrow().len() as i64))
+        }),
+        "get" => NativeFunction::new("get", 1, move |_, arguments| {
+            Ok(map.borrow().get(&arguments[0]).unwrap_or(LoxValue::Nil))
+        }),
+        "set" => NativeFunction::new("set", 2, move |_, arguments| {
+            let mut arguments = arguments.into_iter();
+            let key = arguments.next().unwrap();
+            let value = arguments.next().unwrap();
+            map.borrow_mut().set(key, value.clone())?;
+            Ok(value)
+        }),
+        "has" => NativeFunction::new("has", 1, move |_, arguments| {
+            Ok(LoxValue::Boolean(map.borrow().has(&arguments[0])))
+        }),
+        "delete" => NativeFunction::new("delete", 1, move |_, arguments| {
+            Ok(map
+                .borrow_mut()
+                .delete(&arguments[0])
+                .unwrap_or(LoxValue::Nil))
+        }),
+        "keys" => NativeFunction::new("keys", 0, move |_, _| {
+            let keys = map
+                .borrow()
+                .entries()
+                .iter()
+                .map(|(key, _)| key.clone())
+                .collect();
+            Ok(lox_list::new(keys))
+        }),
+        "values" => NativeFunction::new("values", 0, move |_, _| {
+            let values = map
+                .borrow()
+                .entries()
+                .iter()
+                .map(|(_, value)| value.clone())
+                .collect();
+            Ok(lox_list::new(values))
+        }),
+        _ => {
+            return Err(LoxError::RuntimeError(format!(
+                "Undefined map method '{}'.",
+                name.lexeme()
+            )))
+        }
+    };
+    Ok(LoxValue::NativeFunction(method))
+}
@@ -0,0 +1,78 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::lox_error::LoxError;
+use crate::lox_value::LoxValue;
+
+// Ranges are lazy so `0..1000000` costs the same as `0..10`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LoxRange {
+    start: i64,
+    end: i64,
+    step: i64,
+    inclusive: bool,
+}
+
+impl LoxRange {
+    pub fn new(
+        start: &LoxValue,
+        end: &LoxValue,
+        step: Option<&LoxValue>,
+        inclusive: bool,
+    ) -> Result<LoxValue, LoxError> {
+        let step = match step {
+            Some(step) => bound(step)?,
+            None => 1,
+        };
+        if step == 0 {
+            return Err(LoxError::RuntimeError(
+                "Range step must not be zero.".to_string(),
+            ));
+        }
+
+        Ok(LoxValue::Range(LoxRange {
+            start: bound(start)?,
+            end: bound(end)?,
+            step,
+            inclusive,
+        }))
+    }
+
+    // The value `index` steps from the start, or None once it passes the end
+    pub fn get(&self, index: usize) -> Option<i64> {
+        let offset = i64::try_from(index).ok()?.checked_mul(self.step)?;
+        let value = self.start.checked_add(offset)?;
+        let within = match (self.step > 0, self.inclusive) {
+            (true, true) => value <= self.end,
+            (true, false) => value < self.end,
+            (false, true) => value >= self.end,
+            (false, false) => value > self.end,
+        };
+        if within {
+            Some(value)
+        } else {
+            None
+        }
+    }
+}
+
+fn bound(value: &LoxValue) -> Result<i64, LoxError> {
+    match value {
+        LoxValue::Integer(value) => Ok(*value),
+        _ => Err(LoxError::RuntimeError(format!(
+            "Range bounds must be integers but got {}.",
+            value
+        ))),
+    }
+}
+
+impl fmt::Display for LoxRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operator = if self.inclusive { "..=" } else { ".." };
+        write!(f, "{}{}{}", self.start, operator, self.end)?;
+        if self.step != 1 {
+            write!(f, " step {}", self.step)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use crate::lox_error::LoxError;
+use crate::lox_list;
+use crate::lox_value::LoxValue;
+use crate::native_function::NativeFunction;
+use crate::token::Token;
+
+// Strings are indexed by character rather than by byte so non-ASCII text behaves
+pub fn get(string: &str, index: &LoxValue) -> Result<LoxValue, LoxError> {
+    let length = string.chars().count();
+    let position = lox_list::position(index, length)?;
+    let character = string.chars().nth(position).unwrap();
+    Ok(LoxValue::String(character.to_string()))
+}
+
+pub fn slice(
+    string: &str,
+    start: Option<LoxValue>,
+    end: Option<LoxValue>,
+) -> Result<LoxValue, LoxError> {
+    let length = string.chars().count();
+    let start = lox_list::bound(start, length, 0)?;
+    let end = lox_list::bound(end, length, length)?;
+
+    if start >= end {
+        return Ok(LoxValue::String(String::new()));
+    }
+    Ok(LoxValue::String(
+        string.chars().skip(start).take(end - start).collect(),
+    ))
+}
+
+fn argument(value: &LoxValue, method: &str) -> Result<String, LoxError> {
+    match value {
+        LoxValue::String(value) => Ok(value.clone()),
+        _ => Err(LoxError::RuntimeError(format!(
+            "'{}' expects a string but got {}.",
+            method, value
+        ))),
+    }
+}
+
+pub fn method(string: &str, name: &Token) -> Result<LoxValue, LoxError> {
+    let string = string.to_string();
+    let method = match name.lexeme().as_str() {
+        "len" => NativeFunction::new("len", 0, move |_, _| {
+            Ok(LoxValue::Integer(string.chars().count() as i64))
+        }),
+        "substring" => NativeFunction::new("substring", 2, move |_, arguments| {
+            let mut arguments = arguments.into_iter();
+            slice(&string, arguments.next(), arguments.next())
+        }),
+        "split" => NativeFunction::new("split", 1, move |_, arguments| {
+            let separator = argument(&arguments[0], "split")?;
+            let parts = if separator.is_empty() {
+                string
+                    .chars()
+                    .map(|c| LoxValue::String(c.to_string()))
+                    .collect()
+            } else {
+                string
+                    .split(separator.as_str())
+                    .map(|part| LoxValue::String(part.to_string()))
+                    .collect()
+            };
+            Ok(lox_list::new(parts))
+        }),
+        "join" => NativeFunction::new("join", 1, move |_, arguments| match &arguments[0] {
+            LoxValue::List(list) => {
+                let parts: Vec<String> = list.borrow().iter().map(|v| v.to_string()).collect();
+                Ok(LoxValue::String(parts.join(&string)))
+            }
+            value => Err(LoxError::RuntimeError(format!(
+                "'join' expects a list but got {}.",
+                value
+            ))),
+        }),
+        "trim" => NativeFunction::new("trim", 0, move |_, _| {
+            Ok(LoxValue::String(string.trim().to_string()))
+        }),
+        "upper" => NativeFunction::new("upper", 0, move |_, _| {
+            Ok(LoxValue::String(string.to_uppercase()))
+        }),
+        "lower" => NativeFunction::new("lower", 0, move |_, _| {
+            Ok(LoxValue::String(string.to_lowercase()))
+        }),
+        "find" => NativeFunction::new("find", 1, move |_, arguments| {
+            let needle = argument(&arguments[0], "find")?;
+            let position = match string.find(needle.as_str()) {
+                Some(byte) => string[..byte].chars().count() as i64,
+                None => -1,
+            };
+            Ok(LoxValue::Integer(position))
+        }),
+        "replace" => NativeFunction::new("replace", 2, move |_, arguments| {
+            let from = argument(&arguments[0], "replace")?;
+            let to = argument(&arguments[1], "replace")?;
+            Ok(LoxValue::String(string.replace(from.as_str(), &to)))
+        }),
+        "startsWith" => NativeFunction::new("startsWith", 1, move |_, arguments| {
+            let prefix = argument(&arguments[0], "startsWith")?;
+            Ok(LoxValue::Boolean(string.starts_with(prefix.as_str())))
+        }),
+        "endsWith" => NativeFunction::new("endsWith", 1, move |_, arguments| {
+            let suffix = argument(&arguments[0], "endsWith")?;
+            Ok(LoxValue::Boolean(string.ends_with(suffix.as_str())))
+        }),
+        "chars" => NativeFunction::new("chars", 0, move |_, _| {
+            let characters = string
+                .chars()
+                .map(|c| LoxValue::String(c.to_string()))
+                .collect();
+            Ok(lox_list::new(characters))
+        }),
+        _ => {
+            return Err(LoxError::RuntimeError(format!(
+                "Undefined string method '{}'.",
+                name.lexeme()
+            )))
+        }
+    };
+    Ok(LoxValue::NativeFunction(method))
+}
@@ -1,29 +1,492 @@
+use std::cell::RefCell;
 use std::cmp::{Ordering, PartialOrd};
+use std::convert::TryFrom;
 use std::fmt::Display;
-use std::ops::{Add, Div, Mul, Neg, Not, Sub};
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Not, Rem, Shl, Shr, Sub};
+use std::rc::Rc;
 
+use num_bigint::BigInt;
+use num_traits::{FromPrimitive, ToPrimitive, Zero};
+use rust_decimal::{Decimal, MathematicalOps};
+
+use crate::lox_class::LoxClass;
 use crate::lox_error::LoxError;
 use crate::lox_function::LoxFunction;
+use crate::lox_generator::GeneratorRef;
+use crate::lox_instance::LoxInstance;
+use crate::lox_map::LoxMap;
+use crate::lox_range::LoxRange;
+use crate::native_function::NativeFunction;
+
+// Shifting left by billions of bits would try to build an integer gigabytes long
+const MAX_SHIFT: usize = 1 << 20;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum LoxValue {
     Nil,
     String(String),
-    Number(f32),
+    Number(f64),
+    Integer(i64),
+    BigInteger(BigInt),
+    Decimal(Decimal),
     Boolean(bool),
     Function(LoxFunction),
+    NativeFunction(NativeFunction),
+    Class(LoxClass),
+    Instance(Rc<RefCell<LoxInstance>>),
+    List(Rc<RefCell<Vec<LoxValue>>>),
+    Tuple(Rc<Vec<LoxValue>>),
+    Map(Rc<RefCell<LoxMap>>),
+    Range(LoxRange),
+    Generator(GeneratorRef),
+}
+
+impl PartialEq for LoxValue {
+    fn eq(&self, other: &LoxValue) -> bool {
+        self.equals(other, &mut Vec::new())
+    }
+}
+
+impl LoxValue {
+    // Lists and maps can end up containing themselves, so the pairs already
+    // being compared are tracked and meeting one again counts as equal
+    fn equals(&self, other: &LoxValue, seen: &mut Vec<(usize, usize)>) -> bool {
+        match (self, other) {
+            (LoxValue::Nil, LoxValue::Nil) => true,
+            (LoxValue::String(value), LoxValue::String(other)) => value == other,
+            (LoxValue::Number(value), LoxValue::Number(other)) => value == other,
+            (LoxValue::Integer(value), LoxValue::Integer(other)) => value == other,
+            (LoxValue::BigInteger(value), LoxValue::BigInteger(other)) => value == other,
+            (LoxValue::Decimal(value), LoxValue::Decimal(other)) => value == other,
+            (LoxValue::Number(_), LoxValue::Integer(_))
+            | (LoxValue::Number(_), LoxValue::BigInteger(_))
+            | (LoxValue::Number(_), LoxValue::Decimal(_))
+            | (LoxValue::Integer(_), LoxValue::Number(_))
+            | (LoxValue::Integer(_), LoxValue::Decimal(_))
+            | (LoxValue::BigInteger(_), LoxValue::Number(_))
+            | (LoxValue::BigInteger(_), LoxValue::Decimal(_))
+            | (LoxValue::Decimal(_), _) => {
+                self.whole_number().is_some() && self.whole_number() == other.whole_number()
+            }
+            (LoxValue::Boolean(value), LoxValue::Boolean(other)) => value == other,
+            (LoxValue::Function(value), LoxValue::Function(other)) => value == other,
+            (LoxValue::NativeFunction(value), LoxValue::NativeFunction(other)) => value == other,
+            (LoxValue::Class(value), LoxValue::Class(other)) => value == other,
+            (LoxValue::Instance(value), LoxValue::Instance(other)) => value == other,
+            (LoxValue::List(value), LoxValue::List(other)) => {
+                let pair = (Rc::as_ptr(value) as usize, Rc::as_ptr(other) as usize);
+                if Rc::ptr_eq(value, other) || seen.contains(&pair) {
+                    return true;
+                }
+                seen.push(pair);
+                let equal = all_equal(&value.borrow(), &other.borrow(), seen);
+                seen.pop();
+                equal
+            }
+            (LoxValue::Tuple(value), LoxValue::Tuple(other)) => all_equal(value, other, seen),
+            (LoxValue::Map(value), LoxValue::Map(other)) => {
+                let pair = (Rc::as_ptr(value) as usize, Rc::as_ptr(other) as usize);
+                if Rc::ptr_eq(value, other) || seen.contains(&pair) {
+                    return true;
+                }
+                seen.push(pair);
+                let (value, other) = (value.borrow(), other.borrow());
+                let equal = value.len() == other.len()
+                    && value
+                        .entries()
+                        .iter()
+                        .all(|(key, value)| match other.get(key) {
+                            Some(other) => value.equals(&other, seen),
+                            None => false,
+                        });
+                seen.pop();
+                equal
+            }
+            (LoxValue::Range(value), LoxValue::Range(other)) => value == other,
+            (LoxValue::Generator(value), LoxValue::Generator(other)) => Rc::ptr_eq(value, other),
+            _ => false,
+        }
+    }
+}
+
+fn all_equal(values: &[LoxValue], others: &[LoxValue], seen: &mut Vec<(usize, usize)>) -> bool {
+    values.len() == others.len()
+        && values
+            .iter()
+            .zip(others)
+            .all(|(value, other)| value.equals(other, seen))
+}
+
+// Float keys make this only approximately `Eq`: NaN is never equal to itself
+impl Eq for LoxValue {}
+
+impl Hash for LoxValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Whole floats compare equal to integers so they have to hash the same
+        if let Some(value) = self.as_integer() {
+            return value.hash(state);
+        }
+        if let Some(value) = self.whole_number() {
+            return value.hash(state);
+        }
+
+        mem::discriminant(self).hash(state);
+        match self {
+            LoxValue::Nil => {}
+            LoxValue::String(value) => value.hash(state),
+            LoxValue::Number(value) => value.to_bits().hash(state),
+            LoxValue::Integer(value) => value.hash(state),
+            LoxValue::BigInteger(value) => value.hash(state),
+            LoxValue::Decimal(value) => value.hash(state),
+            LoxValue::Boolean(value) => value.hash(state),
+            LoxValue::Function(function) => function.hash(state),
+            LoxValue::NativeFunction(function) => function.hash(state),
+            LoxValue::Class(class) => class.hash(state),
+            LoxValue::Instance(instance) => Rc::as_ptr(instance).hash(state),
+            LoxValue::List(list) => list.borrow().len().hash(state),
+            LoxValue::Tuple(values) => values.hash(state),
+            LoxValue::Map(map) => map.borrow().len().hash(state),
+            LoxValue::Range(range) => range.hash(state),
+            LoxValue::Generator(generator) => Rc::as_ptr(generator).hash(state),
+        }
+    }
+}
+
+impl LoxValue {
+    // Big integers are only used for values that don't fit in an i64
+    pub fn integer(value: BigInt) -> LoxValue {
+        match value.to_i64() {
+            Some(value) => LoxValue::Integer(value),
+            None => LoxValue::BigInteger(value),
+        }
+    }
+
+    // Integers, and floats or decimals holding a whole number that fits in an i64
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            LoxValue::Integer(value) => Some(*value),
+            LoxValue::Number(value)
+                if value.fract() == 0.0
+                    && *value >= i64::MIN as f64
+                    && *value < i64::MAX as f64 =>
+            {
+                Some(*value as i64)
+            }
+            LoxValue::Decimal(value) if value.is_integer() => value.to_i64(),
+            _ => None,
+        }
+    }
+
+    pub fn to_big_integer(&self) -> Option<BigInt> {
+        match self {
+            LoxValue::Integer(value) => Some(BigInt::from(*value)),
+            LoxValue::BigInteger(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    fn whole_number(&self) -> Option<BigInt> {
+        match self {
+            LoxValue::Number(value) if value.fract() == 0.0 => BigInt::from_f64(*value),
+            LoxValue::Decimal(value) if value.is_integer() => value.to_i128().map(BigInt::from),
+            _ => self.to_big_integer(),
+        }
+    }
+
+    pub fn to_float(&self) -> Option<f64> {
+        match self {
+            LoxValue::Number(value) => Some(*value),
+            LoxValue::Integer(value) => Some(*value as f64),
+            LoxValue::BigInteger(value) => value.to_f64(),
+            LoxValue::Decimal(value) => value.to_f64(),
+            _ => None,
+        }
+    }
+
+    fn to_decimal(&self) -> Option<Decimal> {
+        match self {
+            LoxValue::Decimal(value) => Some(*value),
+            LoxValue::Integer(value) => Some(Decimal::from(*value)),
+            LoxValue::BigInteger(value) => value.to_i128().and_then(Decimal::from_i128),
+            _ => None,
+        }
+    }
+
+    // A big integer outside a decimal's range is an overflow rather than a type mismatch
+    fn decimal_operand(&self, error: &str) -> Result<Decimal, LoxError> {
+        self.to_decimal().ok_or_else(|| match self {
+            LoxValue::BigInteger(_) => LoxError::RuntimeError(format!(
+                "Decimal overflow: {} is too large to convert to a decimal.",
+                self
+            )),
+            _ => LoxError::RuntimeError(error.to_string()),
+        })
+    }
+
+    // Integers stay exact, growing into big integers on overflow, until they meet a float.
+    // Decimals absorb integers but refuse floats, which would silently lose their exactness.
+    fn arithmetic(
+        &self,
+        rhs: &LoxValue,
+        error: &str,
+        checked: fn(i64, i64) -> Option<i64>,
+        big: fn(BigInt, BigInt) -> BigInt,
+        decimal: fn(Decimal, Decimal) -> Option<Decimal>,
+        float: fn(f64, f64) -> f64,
+    ) -> Result<LoxValue, LoxError> {
+        if let (LoxValue::Integer(value), LoxValue::Integer(rhs_value)) = (self, rhs) {
+            if let Some(result) = checked(*value, *rhs_value) {
+                return Ok(LoxValue::Integer(result));
+            }
+        }
+
+        match (self, rhs) {
+            (LoxValue::Number(_), LoxValue::Decimal(_))
+            | (LoxValue::Decimal(_), LoxValue::Number(_)) => {
+                return Err(LoxError::RuntimeError(
+                    "Can't mix decimals and floats, convert with decimal() first.".to_string(),
+                ))
+            }
+            (LoxValue::Decimal(_), _) | (_, LoxValue::Decimal(_)) => {
+                return decimal(self.decimal_operand(error)?, rhs.decimal_operand(error)?)
+                    .map(LoxValue::Decimal)
+                    .ok_or_else(|| LoxError::RuntimeError("Decimal overflow.".to_string()));
+            }
+            _ => {}
+        }
+
+        match (self.to_big_integer(), rhs.to_big_integer()) {
+            (Some(value), Some(rhs_value)) => Ok(LoxValue::integer(big(value, rhs_value))),
+            _ => match (self.to_float(), rhs.to_float()) {
+                (Some(value), Some(rhs_value)) => Ok(LoxValue::Number(float(value, rhs_value))),
+                _ => Err(LoxError::RuntimeError(error.to_string())),
+            },
+        }
+    }
+
+    // Integers stay exact when raised to a non-negative integer power
+    pub fn pow(self, rhs: LoxValue) -> Result<LoxValue, LoxError> {
+        let error = "Can't raise these two values to a power";
+
+        match (&self, &rhs) {
+            (LoxValue::Number(_), LoxValue::Decimal(_))
+            | (LoxValue::Decimal(_), LoxValue::Number(_)) => {
+                return Err(LoxError::RuntimeError(
+                    "Can't mix decimals and floats, convert with decimal() first.".to_string(),
+                ))
+            }
+            (LoxValue::Decimal(_), _) | (_, LoxValue::Decimal(_)) => {
+                return match (self.to_decimal(), rhs.as_integer()) {
+                    (Some(value), Some(exponent)) if value.is_zero() && exponent < 0 => {
+                        Err(LoxError::RuntimeError("Division by zero.".to_string()))
+                    }
+                    (Some(value), Some(exponent)) => value
+                        .checked_powi(exponent)
+                        .map(LoxValue::Decimal)
+                        .ok_or_else(|| LoxError::RuntimeError("Decimal overflow.".to_string())),
+                    (Some(_), None) if rhs.to_decimal().is_some() => Err(LoxError::RuntimeError(
+                        "Decimals can only be raised to whole number powers.".to_string(),
+                    )),
+                    _ => Err(LoxError::RuntimeError(error.to_string())),
+                }
+            }
+            _ => {}
+        }
+
+        if let (Some(value), LoxValue::Integer(exponent)) = (self.to_big_integer(), &rhs) {
+            if *exponent < 0 && value.is_zero() {
+                return Err(LoxError::RuntimeError("Division by zero.".to_string()));
+            }
+            if let Ok(exponent) = u32::try_from(*exponent) {
+                return Ok(LoxValue::integer(value.pow(exponent)));
+            }
+        }
+
+        match (self.to_float(), rhs.to_float()) {
+            (Some(value), Some(rhs_value)) => Ok(LoxValue::Number(value.powf(rhs_value))),
+            _ => Err(LoxError::RuntimeError(error.to_string())),
+        }
+    }
+
+    // `~/` truncates toward zero so that it pairs up with `%`
+    pub fn int_div(self, rhs: LoxValue) -> Result<LoxValue, LoxError> {
+        if rhs.is_exact_zero() && self.is_exact() {
+            return Err(LoxError::RuntimeError("Division by zero.".to_string()));
+        }
+
+        self.arithmetic(
+            &rhs,
+            "Can't divide these two values",
+            i64::checked_div,
+            |a, b| a / b,
+            |a, b| a.checked_div(b).map(|value| value.trunc()),
+            |a, b| (a / b).trunc(),
+        )
+    }
+
+    pub fn bit_not(self) -> Result<LoxValue, LoxError> {
+        match self {
+            LoxValue::Integer(value) => Ok(LoxValue::Integer(!value)),
+            LoxValue::BigInteger(value) => Ok(LoxValue::integer(!value)),
+            _ => Err(LoxError::RuntimeError(
+                "Bitwise operators only apply to integers.".to_string(),
+            )),
+        }
+    }
+
+    fn bitwise(
+        &self,
+        rhs: &LoxValue,
+        small: fn(i64, i64) -> i64,
+        big: fn(BigInt, BigInt) -> BigInt,
+    ) -> Result<LoxValue, LoxError> {
+        if let (LoxValue::Integer(value), LoxValue::Integer(rhs_value)) = (self, rhs) {
+            return Ok(LoxValue::Integer(small(*value, *rhs_value)));
+        }
+
+        match (self.to_big_integer(), rhs.to_big_integer()) {
+            (Some(value), Some(rhs_value)) => Ok(LoxValue::integer(big(value, rhs_value))),
+            _ => Err(LoxError::RuntimeError(
+                "Bitwise operators only apply to integers.".to_string(),
+            )),
+        }
+    }
+
+    // Shifts go through big integers so `<<` never overflows
+    fn shift(
+        &self,
+        amount: &LoxValue,
+        shift: fn(BigInt, usize) -> BigInt,
+    ) -> Result<LoxValue, LoxError> {
+        let amount = match amount {
+            LoxValue::Integer(_) | LoxValue::BigInteger(_) => amount
+                .as_integer()
+                .and_then(|amount| usize::try_from(amount).ok())
+                .filter(|amount| *amount <= MAX_SHIFT)
+                .ok_or_else(|| {
+                    LoxError::RuntimeError(format!(
+                        "Shift amount must be between 0 and {}.",
+                        MAX_SHIFT
+                    ))
+                })?,
+            _ => {
+                return Err(LoxError::RuntimeError(
+                    "Bitwise operators only apply to integers.".to_string(),
+                ))
+            }
+        };
+
+        match self.to_big_integer() {
+            Some(value) => Ok(LoxValue::integer(shift(value, amount))),
+            None => Err(LoxError::RuntimeError(
+                "Bitwise operators only apply to integers.".to_string(),
+            )),
+        }
+    }
+
+    // Integers and decimals raise on division by zero instead of producing infinity
+    fn is_exact(&self) -> bool {
+        matches!(
+            self,
+            LoxValue::Integer(_) | LoxValue::BigInteger(_) | LoxValue::Decimal(_)
+        )
+    }
+
+    fn is_exact_zero(&self) -> bool {
+        match self {
+            LoxValue::Decimal(value) => value.is_zero(),
+            _ => self.to_big_integer().is_some_and(|value| value.is_zero()),
+        }
+    }
 }
 
 impl Display for LoxValue {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.write(f, &mut Vec::new())
+    }
+}
+
+impl LoxValue {
+    // A list or map that contains itself is written as `[...]` or `{...}` where it repeats
+    fn write(&self, f: &mut std::fmt::Formatter, seen: &mut Vec<usize>) -> std::fmt::Result {
         match self {
             LoxValue::Boolean(value) => write!(f, "{}", value),
+            // Rounding to 15 significant digits hides binary noise like 0.1 + 0.2
+            LoxValue::Number(value) if value.is_finite() => {
+                let rounded: f64 = format!("{:.14e}", value).parse().unwrap();
+                write!(f, "{}", rounded)
+            }
             LoxValue::Number(value) => write!(f, "{}", value),
+            LoxValue::Integer(value) => write!(f, "{}", value),
+            LoxValue::BigInteger(value) => write!(f, "{}", value),
+            LoxValue::Decimal(value) => write!(f, "{}", value),
             LoxValue::String(value) => write!(f, "{}", value),
             LoxValue::Nil => write!(f, "nil"),
             LoxValue::Function(function) => write!(f, "function {}", function.name()),
+            LoxValue::NativeFunction(function) => {
+                write!(f, "native function {}", function.name())
+            }
+            LoxValue::Class(class) => write!(f, "class {}", class.name()),
+            LoxValue::Instance(instance) => {
+                write!(f, "{} instance", instance.borrow().class().name())
+            }
+            LoxValue::List(list) => {
+                let address = Rc::as_ptr(list) as usize;
+                if seen.contains(&address) {
+                    return write!(f, "[...]");
+                }
+                seen.push(address);
+                write!(f, "[")?;
+                write_all(f, &list.borrow(), seen)?;
+                seen.pop();
+                write!(f, "]")
+            }
+            LoxValue::Tuple(values) => {
+                write!(f, "(")?;
+                write_all(f, values, seen)?;
+                if values.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            LoxValue::Map(map) => {
+                let address = Rc::as_ptr(map) as usize;
+                if seen.contains(&address) {
+                    return write!(f, "{{...}}");
+                }
+                seen.push(address);
+                write!(f, "{{")?;
+                for (index, (key, value)) in map.borrow().entries().iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    key.write(f, seen)?;
+                    write!(f, ": ")?;
+                    value.write(f, seen)?;
+                }
+                seen.pop();
+                write!(f, "}}")
+            }
+            LoxValue::Range(range) => write!(f, "{}", range),
+            LoxValue::Generator(generator) => write!(f, "generator {}", generator.name()),
+        }
+    }
+}
+
+fn write_all(
+    f: &mut std::fmt::Formatter,
+    values: &[LoxValue],
+    seen: &mut Vec<usize>,
+) -> std::fmt::Result {
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
         }
+        value.write(f, seen)?;
     }
+    Ok(())
 }
 
 impl Neg for LoxValue {
@@ -33,15 +496,40 @@ impl Neg for LoxValue {
         match self {
             LoxValue::Nil => Ok(LoxValue::Boolean(true)),
             LoxValue::Number(value) => Ok(LoxValue::Number(-value)),
+            LoxValue::Integer(value) => match value.checked_neg() {
+                Some(value) => Ok(LoxValue::Integer(value)),
+                None => Ok(LoxValue::integer(-BigInt::from(value))),
+            },
+            LoxValue::BigInteger(value) => Ok(LoxValue::integer(-value)),
+            LoxValue::Decimal(value) => Ok(LoxValue::Decimal(-value)),
             LoxValue::Boolean(_) => Err(LoxError::RuntimeError(
                 "Boolean values cannot be negated".to_string(),
             )),
             LoxValue::String(_) => Err(LoxError::RuntimeError(
                 "String values cannot be negated".to_string(),
             )),
-            LoxValue::Function(_) => Err(LoxError::RuntimeError(
+            LoxValue::Function(_) | LoxValue::NativeFunction(_) => Err(LoxError::RuntimeError(
                 "Functions cannot be negated".to_string(),
             )),
+            LoxValue::Class(_) => Err(LoxError::RuntimeError(
+                "Classes cannot be negated".to_string(),
+            )),
+            LoxValue::Instance(_) => Err(LoxError::RuntimeError(
+                "Instances cannot be negated".to_string(),
+            )),
+            LoxValue::List(_) => Err(LoxError::RuntimeError(
+                "Lists cannot be negated".to_string(),
+            )),
+            LoxValue::Tuple(_) => Err(LoxError::RuntimeError(
+                "Tuples cannot be negated".to_string(),
+            )),
+            LoxValue::Map(_) => Err(LoxError::RuntimeError("Maps cannot be negated".to_string())),
+            LoxValue::Range(_) => Err(LoxError::RuntimeError(
+                "Ranges cannot be negated".to_string(),
+            )),
+            LoxValue::Generator(_) => Err(LoxError::RuntimeError(
+                "Generators cannot be negated".to_string(),
+            )),
         }
     }
 }
@@ -59,30 +547,91 @@ impl Not for LoxValue {
 }
 
 impl Div for LoxValue {
-    type Output = LoxValue;
+    type Output = Result<LoxValue, LoxError>;
 
-    fn div(self, rhs: LoxValue) -> LoxValue {
-        match self {
-            LoxValue::Number(value) => match rhs {
-                LoxValue::Number(rhs_value) => LoxValue::Number(value / rhs_value),
-                _ => panic!("Can't divide these two values"),
+    fn div(self, rhs: LoxValue) -> Result<LoxValue, LoxError> {
+        if rhs.is_exact_zero() && self.is_exact() {
+            return Err(LoxError::RuntimeError("Division by zero.".to_string()));
+        }
+
+        if let (LoxValue::Decimal(_), _) | (_, LoxValue::Decimal(_)) = (&self, &rhs) {
+            return self.arithmetic(
+                &rhs,
+                "Can't divide these two values",
+                |_, _| None,
+                |a, b| a / b,
+                Decimal::checked_div,
+                |a, b| a / b,
+            );
+        }
+
+        // Integer division stays exact when it can and falls back to a float otherwise
+        match (self.to_big_integer(), rhs.to_big_integer()) {
+            (Some(value), Some(rhs_value)) if (&value % &rhs_value).is_zero() => {
+                Ok(LoxValue::integer(value / rhs_value))
+            }
+            (Some(value), Some(rhs_value)) => divide_to_float(value, rhs_value),
+            _ => match (self.to_float(), rhs.to_float()) {
+                (Some(value), Some(rhs_value)) => Ok(LoxValue::Number(value / rhs_value)),
+                _ => Err(LoxError::RuntimeError(
+                    "Can't divide these two values".to_string(),
+                )),
             },
-            _ => panic!("Can't divide these two values"),
         }
     }
 }
 
-impl Sub for LoxValue {
-    type Output = LoxValue;
+// Splits off the whole part first so big operands don't overflow to infinity
+// on their own, which leaves only quotients too big for any float to fail
+fn divide_to_float(value: BigInt, rhs: BigInt) -> Result<LoxValue, LoxError> {
+    let quotient = &value / &rhs;
+    let remainder = value - &quotient * &rhs;
+    // A float only keeps the leading bits, so drop the rest before converting
+    let shift = rhs.bits().saturating_sub(64);
+    let fraction = match ((remainder >> shift).to_f64(), (rhs >> shift).to_f64()) {
+        (Some(remainder), Some(rhs)) => remainder / rhs,
+        _ => 0.0,
+    };
 
-    fn sub(self, rhs: LoxValue) -> LoxValue {
-        match self {
-            LoxValue::Number(value) => match rhs {
-                LoxValue::Number(rhs_value) => LoxValue::Number(value - rhs_value),
-                _ => panic!("Can't subtract these two values"),
-            },
-            _ => panic!("Can't subtract these two values"),
+    match quotient.to_f64().map(|quotient| quotient + fraction) {
+        Some(result) if result.is_finite() => Ok(LoxValue::Number(result)),
+        _ => Err(LoxError::RuntimeError(
+            "Quotient is too large for a float, use ~/ for integer division.".to_string(),
+        )),
+    }
+}
+
+impl Rem for LoxValue {
+    type Output = Result<LoxValue, LoxError>;
+
+    fn rem(self, rhs: LoxValue) -> Result<LoxValue, LoxError> {
+        if rhs.is_exact_zero() && self.is_exact() {
+            return Err(LoxError::RuntimeError("Division by zero.".to_string()));
         }
+
+        self.arithmetic(
+            &rhs,
+            "Can't take the remainder of these two values",
+            i64::checked_rem,
+            |a, b| a % b,
+            Decimal::checked_rem,
+            |a, b| a % b,
+        )
+    }
+}
+
+impl Sub for LoxValue {
+    type Output = Result<LoxValue, LoxError>;
+
+    fn sub(self, rhs: LoxValue) -> Result<LoxValue, LoxError> {
+        self.arithmetic(
+            &rhs,
+            "Can't subtract these two values",
+            i64::checked_sub,
+            |a, b| a - b,
+            Decimal::checked_sub,
+            |a, b| a - b,
+        )
     }
 }
 
@@ -91,19 +640,26 @@ impl Add for LoxValue {
 
     fn add(self, rhs: LoxValue) -> Result<LoxValue, LoxError> {
         match self {
-            LoxValue::Number(value) => match rhs {
-                LoxValue::Number(rhs_value) => Ok(LoxValue::Number(value + rhs_value)),
-                _ => Err(LoxError::RuntimeError(
-                    "right hand side must also be a number".to_string(),
-                )),
-            },
+            LoxValue::Number(_)
+            | LoxValue::Integer(_)
+            | LoxValue::BigInteger(_)
+            | LoxValue::Decimal(_) => self.arithmetic(
+                &rhs,
+                "right hand side must also be a number",
+                i64::checked_add,
+                |a, b| a + b,
+                Decimal::checked_add,
+                |a, b| a + b,
+            ),
             LoxValue::String(value) => match rhs {
                 LoxValue::String(rhs_value) => {
                     let mut new_str = value.clone();
                     new_str.push_str(&rhs_value);
                     Ok(LoxValue::String(new_str))
                 }
-                _ => panic!("TypeError: Can't add a string to a number."),
+                _ => Err(LoxError::RuntimeError(
+                    "Can't add a string to a non-string value.".to_string(),
+                )),
             },
             LoxValue::Boolean(_value) => Err(LoxError::RuntimeError(
                 "Cannot add value to boolean.".to_string(),
@@ -111,24 +667,93 @@ impl Add for LoxValue {
             LoxValue::Nil => Err(LoxError::RuntimeError(
                 "Cannot add value to nil.".to_string(),
             )),
-            LoxValue::Function(_) => Err(LoxError::RuntimeError(
+            LoxValue::Function(_) | LoxValue::NativeFunction(_) => Err(LoxError::RuntimeError(
                 "Cannot add value to a function".to_string(),
             )),
+            LoxValue::Class(_) => Err(LoxError::RuntimeError(
+                "Cannot add value to a class".to_string(),
+            )),
+            LoxValue::Instance(_) => Err(LoxError::RuntimeError(
+                "Cannot add value to an instance".to_string(),
+            )),
+            LoxValue::List(list) => match rhs {
+                LoxValue::List(rhs_list) => {
+                    let mut values = list.borrow().clone();
+                    values.extend(rhs_list.borrow().iter().cloned());
+                    Ok(LoxValue::List(Rc::new(RefCell::new(values))))
+                }
+                _ => Err(LoxError::RuntimeError(
+                    "Can't add a list to a non-list value.".to_string(),
+                )),
+            },
+            LoxValue::Tuple(_) => Err(LoxError::RuntimeError(
+                "Cannot add value to a tuple".to_string(),
+            )),
+            LoxValue::Map(_) => Err(LoxError::RuntimeError(
+                "Cannot add value to a map".to_string(),
+            )),
+            LoxValue::Range(_) => Err(LoxError::RuntimeError(
+                "Cannot add value to a range".to_string(),
+            )),
+            LoxValue::Generator(_) => Err(LoxError::RuntimeError(
+                "Cannot add value to a generator".to_string(),
+            )),
         }
     }
 }
 
 impl Mul for LoxValue {
-    type Output = LoxValue;
+    type Output = Result<LoxValue, LoxError>;
 
-    fn mul(self, rhs: LoxValue) -> LoxValue {
-        match self {
-            LoxValue::Number(value) => match rhs {
-                LoxValue::Number(rhs_value) => LoxValue::Number(value * rhs_value),
-                _ => panic!("Can't multiply these two values"),
-            },
-            _ => panic!("Can't multiply these two values"),
-        }
+    fn mul(self, rhs: LoxValue) -> Result<LoxValue, LoxError> {
+        self.arithmetic(
+            &rhs,
+            "Can't multiply these two values",
+            i64::checked_mul,
+            |a, b| a * b,
+            Decimal::checked_mul,
+            |a, b| a * b,
+        )
+    }
+}
+
+impl BitAnd for LoxValue {
+    type Output = Result<LoxValue, LoxError>;
+
+    fn bitand(self, rhs: LoxValue) -> Result<LoxValue, LoxError> {
+        self.bitwise(&rhs, |a, b| a & b, |a, b| a & b)
+    }
+}
+
+impl BitOr for LoxValue {
+    type Output = Result<LoxValue, LoxError>;
+
+    fn bitor(self, rhs: LoxValue) -> Result<LoxValue, LoxError> {
+        self.bitwise(&rhs, |a, b| a | b, |a, b| a | b)
+    }
+}
+
+impl BitXor for LoxValue {
+    type Output = Result<LoxValue, LoxError>;
+
+    fn bitxor(self, rhs: LoxValue) -> Result<LoxValue, LoxError> {
+        self.bitwise(&rhs, |a, b| a ^ b, |a, b| a ^ b)
+    }
+}
+
+impl Shl for LoxValue {
+    type Output = Result<LoxValue, LoxError>;
+
+    fn shl(self, rhs: LoxValue) -> Result<LoxValue, LoxError> {
+        self.shift(&rhs, |value, amount| value << amount)
+    }
+}
+
+impl Shr for LoxValue {
+    type Output = Result<LoxValue, LoxError>;
+
+    fn shr(self, rhs: LoxValue) -> Result<LoxValue, LoxError> {
+        self.shift(&rhs, |value, amount| value >> amount)
     }
 }
 
@@ -139,11 +764,49 @@ impl PartialOrd for LoxValue {
                 LoxValue::Nil => Some(Ordering::Equal),
                 _ => Some(Ordering::Greater),
             },
-            LoxValue::Number(value) => match other {
-                LoxValue::Number(other_value) => value.partial_cmp(other_value),
-                _ => panic!("Can't compare a number with this value"),
-            },
-            _ => panic!("Can't compare these two types"),
+            LoxValue::Number(_)
+            | LoxValue::Integer(_)
+            | LoxValue::BigInteger(_)
+            | LoxValue::Decimal(_) => {
+                if let (Some(value), Some(other_value)) =
+                    (self.to_big_integer(), other.to_big_integer())
+                {
+                    return value.partial_cmp(&other_value);
+                }
+                if let (Some(value), LoxValue::Number(other_value)) = (self.to_big_integer(), other)
+                {
+                    return compare_with_float(&value, *other_value);
+                }
+                if let (LoxValue::Number(value), Some(other_value)) = (self, other.to_big_integer())
+                {
+                    return compare_with_float(&other_value, *value).map(Ordering::reverse);
+                }
+                match (self.to_decimal(), other.to_decimal()) {
+                    (Some(value), Some(other_value)) => value.partial_cmp(&other_value),
+                    _ => self.to_float()?.partial_cmp(&other.to_float()?),
+                }
+            }
+            _ => None,
         }
     }
 }
+
+// Converting a big integer to a float can round it, so compare against the
+// float's whole part instead, which agrees with how `==` treats them
+fn compare_with_float(value: &BigInt, float: f64) -> Option<Ordering> {
+    if float.is_nan() {
+        return None;
+    }
+    if float.is_infinite() {
+        return Some(if float > 0.0 {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        });
+    }
+
+    match value.cmp(&BigInt::from_f64(float.floor())?) {
+        Ordering::Equal if float.fract() != 0.0 => Some(Ordering::Less),
+        ordering => Some(ordering),
+    }
+}
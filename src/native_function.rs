@@ -0,0 +1,66 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use crate::interpreter::Interpreter;
+use crate::lox_callable::LoxCallable;
+use crate::lox_error::LoxError;
+use crate::lox_value::LoxValue;
+
+pub type NativeFn = Rc<dyn Fn(&mut Interpreter, Vec<LoxValue>) -> Result<LoxValue, LoxError>>;
+
+#[derive(Clone)]
+pub struct NativeFunction {
+    name: String,
+    arity: usize,
+    function: NativeFn,
+}
+
+impl LoxCallable for NativeFunction {
+    fn arity(&self) -> usize {
+        self.arity
+    }
+
+    fn call(
+        self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, LoxError> {
+        (self.function)(interpreter, arguments)
+    }
+}
+
+impl PartialEq for NativeFunction {
+    fn eq(&self, other: &NativeFunction) -> bool {
+        Rc::ptr_eq(&self.function, &other.function)
+    }
+}
+
+impl Hash for NativeFunction {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (Rc::as_ptr(&self.function) as *const ()).hash(state);
+    }
+}
+
+impl fmt::Debug for NativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "NativeFunction({}/{})", self.name, self.arity)
+    }
+}
+
+impl NativeFunction {
+    pub fn new<F>(name: &str, arity: usize, function: F) -> NativeFunction
+    where
+        F: Fn(&mut Interpreter, Vec<LoxValue>) -> Result<LoxValue, LoxError> + 'static,
+    {
+        NativeFunction {
+            name: name.to_string(),
+            arity,
+            function: Rc::new(function),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+}
@@ -1,44 +1,102 @@
 use std::error::Error;
 use std::fmt;
 use std::fmt::Display;
+use std::mem;
 use std::str::FromStr;
 
-use crate::expr::{Assign, Binary, BoxedExpr, Call, Grouping, Literal, Logical, Unary, Variable};
+use num_bigint::BigInt;
+
+use crate::expr::{
+    Assign, AssignPattern, Binary, BoxedExpr, Call, Conditional, Get, Grouping, Interpolation,
+    Lambda, List, Literal, Logical, Map, OptionalChain, Range, Set, Slice, Subscript, SubscriptSet,
+    Super, This, Tuple, Unary, Update, Variable,
+};
+use crate::lox_decimal;
 use crate::lox_value::LoxValue;
-use crate::stmt::{Block, Expression, Function, If, Print, Return, Stmt, Var, While};
+use crate::pattern::Pattern;
+use crate::stmt::{
+    Block, Break, Class, Continue, Expression, ForIn, Function, If, Print, Return, Stmt, Throw,
+    Try, Var, VarPattern, While, Yield,
+};
 use crate::token::{Token, TokenType};
 
 pub struct Parser {
     tokens: Vec<Token>,
     index: usize,
+    // Whether the function body being parsed has a `yield` in it so far
+    yields: bool,
+    // Mistakes the parser could carry on past, reported together at the end
+    errors: Vec<ParserError>,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Parser {
-        Parser { tokens, index: 0 }
+        Parser {
+            tokens,
+            index: 0,
+            yields: false,
+            errors: Vec::new(),
+        }
     }
 
-    pub fn parse(&mut self) -> Vec<Box<dyn Stmt>> {
+    pub fn parse(&mut self) -> Result<Vec<Box<dyn Stmt>>, Vec<ParserError>> {
         let mut statements: Vec<Box<dyn Stmt>> = Vec::new();
         while !self.is_end() {
             statements.push(self.declaration());
         }
-        statements
+        if self.errors.is_empty() {
+            Ok(statements)
+        } else {
+            Err(mem::take(&mut self.errors))
+        }
     }
 
     // Statements
     fn declaration(&mut self) -> Box<dyn Stmt> {
-        if self.matches(&[TokenType::Fun]) {
-            return self.function("function");
+        let doc = self.peek().and_then(|token| token.doc());
+        if self.matches(&[TokenType::Class]) {
+            return self.class_declaration(doc);
         }
-        if self.matches(&[TokenType::Var]) {
+        if self.check(TokenType::Fun) && self.check_next(TokenType::Identifier) {
+            self.next();
+            return self.function("function", doc);
+        }
+        if self.matches(&[TokenType::Var, TokenType::Const]) {
             return self.var_declaration();
         }
 
         self.statement()
     }
 
-    fn function(&mut self, kind: &str) -> Box<dyn Stmt> {
+    fn class_declaration(&mut self, doc: Option<String>) -> Box<dyn Stmt> {
+        let name = self
+            .consume(TokenType::Identifier, "Expect class name.")
+            .unwrap();
+
+        let superclass = if self.matches(&[TokenType::Less]) {
+            self.consume(TokenType::Identifier, "Expect superclass name.")
+                .ok();
+            Some(*Variable::new(self.previous()))
+        } else {
+            None
+        };
+
+        self.consume(TokenType::LeftBrace, "Expect '{' before class body.")
+            .ok();
+
+        let mut methods: Vec<Function> = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_end() {
+            let doc = self.peek().and_then(|token| token.doc());
+            methods.push(*self.function("method", doc));
+        }
+
+        self.consume(TokenType::RightBrace, "Expect '}' after class body.")
+            .ok();
+
+        Class::new(name.with_doc(doc), superclass, methods)
+    }
+
+    fn function(&mut self, kind: &str, doc: Option<String>) -> Box<Function> {
         let name = self
             .consume(
                 TokenType::Identifier,
@@ -46,12 +104,35 @@ impl Parser {
             )
             .unwrap();
 
+        self.function_body(name, kind, doc)
+    }
+
+    fn function_body(&mut self, name: Token, kind: &str, doc: Option<String>) -> Box<Function> {
         self.consume(
             TokenType::LeftParen,
             format!("Expect '(' after {} name", kind).as_str(),
         )
         .ok();
 
+        let parameters = self.parameters();
+
+        self.consume(TokenType::RightParen, "Expect ')' after parameters")
+            .ok();
+
+        self.consume(
+            TokenType::LeftBrace,
+            format!("Expect '{{' before {} body.", kind).as_str(),
+        )
+        .ok();
+
+        let enclosing = mem::replace(&mut self.yields, false);
+        let body = self.block();
+        let generator = mem::replace(&mut self.yields, enclosing);
+
+        Function::new(name.with_doc(doc), parameters, body, generator)
+    }
+
+    fn parameters(&mut self) -> Vec<Token> {
         let mut parameters: Vec<Token> = Vec::new();
 
         if !self.check(TokenType::RightParen) {
@@ -70,21 +151,19 @@ impl Parser {
                 }
             }
         }
-        self.consume(TokenType::RightParen, "Expect ')' after parameters")
-            .ok();
-
-        self.consume(
-            TokenType::LeftBrace,
-            format!("Expect '{{' before {} body.", kind).as_str(),
-        )
-        .ok();
-
-        let body = self.block();
 
-        Function::new(name, parameters, body)
+        parameters
     }
 
     fn var_declaration(&mut self) -> Box<dyn Stmt> {
+        let constant = self.previous().token_type() == TokenType::Const;
+        if self.check(TokenType::LeftParen)
+            || self.check(TokenType::LeftBracket)
+            || self.check(TokenType::LeftBrace)
+        {
+            return self.var_pattern(constant);
+        }
+
         let name = self
             .consume(TokenType::Identifier, "Expected variable name")
             .unwrap();
@@ -100,10 +179,104 @@ impl Parser {
             "Expect ';' after variable declaration.",
         )
         .ok();
-        Var::new(name, initializer)
+        Var::new(name, initializer, constant)
+    }
+
+    fn var_pattern(&mut self, constant: bool) -> Box<dyn Stmt> {
+        let pattern = self.binding_pattern();
+
+        self.consume(TokenType::Equal, "Expect '=' after destructuring pattern.")
+            .ok();
+        let initializer = self.expression();
+
+        self.consume(
+            TokenType::Semicolon,
+            "Expect ';' after variable declaration.",
+        )
+        .ok();
+        VarPattern::new(pattern, initializer, constant)
+    }
+
+    fn binding_pattern(&mut self) -> Pattern {
+        if self.matches(&[TokenType::LeftParen, TokenType::LeftBracket]) {
+            let bracket = self.previous();
+            let close = if bracket.token_type() == TokenType::LeftParen {
+                TokenType::RightParen
+            } else {
+                TokenType::RightBracket
+            };
+
+            let mut patterns = Vec::new();
+            while !self.check(close) && !self.is_end() {
+                if self.matches(&[TokenType::Ellipsis]) {
+                    let name = self
+                        .consume(TokenType::Identifier, "Expect variable name after '...'.")
+                        .unwrap();
+                    patterns.push(Pattern::Rest(Variable::new(name)));
+                } else {
+                    patterns.push(self.binding_pattern());
+                }
+
+                if !self.matches(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+            self.consume(close, "Expect closing bracket after pattern.")
+                .ok();
+
+            if patterns
+                .iter()
+                .filter(|pattern| matches!(pattern, Pattern::Rest(_)))
+                .count()
+                > 1
+            {
+                self.error(&bracket, "Cannot have more than one '...' in a pattern.");
+            }
+            return Pattern::Sequence(bracket, patterns);
+        }
+
+        if self.matches(&[TokenType::LeftBrace]) {
+            let brace = self.previous();
+            let mut names = Vec::new();
+            while !self.check(TokenType::RightBrace) && !self.is_end() {
+                names.push(
+                    self.consume(TokenType::Identifier, "Expect field name in pattern.")
+                        .unwrap(),
+                );
+
+                if !self.matches(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+            self.consume(TokenType::RightBrace, "Expect '}' after pattern.")
+                .ok();
+            return Pattern::Fields(brace, names);
+        }
+
+        if !self.check(TokenType::Identifier) {
+            if let Some(token) = self.peek() {
+                self.error(&token, "Expect variable name in pattern.");
+            }
+        }
+        let name = self.next().unwrap_or_else(|| self.previous());
+        Pattern::Target(Variable::new(name))
     }
 
     fn statement(&mut self) -> Box<dyn Stmt> {
+        if self.matches(&[TokenType::Break]) {
+            let keyword = self.previous();
+            self.consume(TokenType::Semicolon, "Expect ';' after 'break'.")
+                .ok();
+            return Break::new(keyword);
+        }
+
+        if self.matches(&[TokenType::Continue]) {
+            let keyword = self.previous();
+            self.consume(TokenType::Semicolon, "Expect ';' after 'continue'.")
+                .ok();
+            return Continue::new(keyword);
+        }
+
         if self.matches(&[TokenType::For]) {
             return self.for_statement();
         }
@@ -120,10 +293,22 @@ impl Parser {
             return self.return_statement();
         }
 
+        if self.matches(&[TokenType::Throw]) {
+            return self.throw_statement();
+        }
+
+        if self.matches(&[TokenType::Try]) {
+            return self.try_statement();
+        }
+
         if self.matches(&[TokenType::While]) {
             return self.while_statement();
         }
 
+        if self.matches(&[TokenType::Yield]) {
+            return self.yield_statement();
+        }
+
         if self.matches(&[TokenType::LeftBrace]) {
             return Block::new(self.block());
         }
@@ -135,6 +320,10 @@ impl Parser {
         self.consume(TokenType::LeftParen, "Expect '(' after 'for'.")
             .ok();
 
+        if self.is_for_in() {
+            return self.for_in_statement();
+        }
+
         let initializer = if self.matches(&[TokenType::Semicolon]) {
             None
         } else if self.matches(&[TokenType::Var]) {
@@ -161,16 +350,12 @@ impl Parser {
 
         let mut body = self.statement();
 
-        if let Some(increment) = increment {
-            body = Block::new(vec![body, Expression::new(increment)])
-        }
-
         let for_condition = match condition {
             Some(condition) => condition,
             None => Literal::new(LoxValue::Boolean(true)),
         };
 
-        body = While::new(for_condition, body);
+        body = While::with_increment(for_condition, body, increment);
 
         if let Some(initializer) = initializer {
             body = Block::new(vec![initializer, body])
@@ -179,6 +364,25 @@ impl Parser {
         body
     }
 
+    // The loop always declares its variables, so `var` is optional and
+    // `const` keeps the body from reassigning them
+    fn for_in_statement(&mut self) -> Box<dyn Stmt> {
+        let constant = self.matches(&[TokenType::Const]);
+        if !constant {
+            self.matches(&[TokenType::Var]);
+        }
+        let pattern = self.binding_pattern();
+        let keyword = self
+            .consume(TokenType::In, "Expect 'in' after loop variable.")
+            .unwrap();
+        let iterable = self.expression();
+        self.consume(TokenType::RightParen, "Expect ')' after iterable.")
+            .ok();
+
+        let body = self.statement();
+        ForIn::new(pattern, keyword, iterable, body, constant)
+    }
+
     fn if_statement(&mut self) -> Box<dyn Stmt> {
         self.consume(TokenType::LeftParen, "Expect '(' after 'if'.")
             .ok();
@@ -206,9 +410,9 @@ impl Parser {
     fn return_statement(&mut self) -> Box<dyn Stmt> {
         let previous = self.previous();
         let value = if !self.check(TokenType::Semicolon) {
-            self.expression()
+            Some(self.expression())
         } else {
-            Literal::nil()
+            None
         };
 
         self.consume(TokenType::Semicolon, "Expect ';' after value.")
@@ -217,6 +421,67 @@ impl Parser {
         Return::new(previous, value)
     }
 
+    fn yield_statement(&mut self) -> Box<dyn Stmt> {
+        let keyword = self.previous();
+        let value = if !self.check(TokenType::Semicolon) {
+            Some(self.expression())
+        } else {
+            None
+        };
+
+        self.consume(TokenType::Semicolon, "Expect ';' after yielded value.")
+            .ok();
+
+        self.yields = true;
+        Yield::new(keyword, value)
+    }
+
+    fn throw_statement(&mut self) -> Box<dyn Stmt> {
+        let keyword = self.previous();
+        let value = self.expression();
+        self.consume(TokenType::Semicolon, "Expect ';' after thrown value.")
+            .ok();
+
+        Throw::new(keyword, value)
+    }
+
+    fn try_statement(&mut self) -> Box<dyn Stmt> {
+        let keyword = self.previous();
+        self.consume(TokenType::LeftBrace, "Expect '{' after 'try'.")
+            .ok();
+        let body = self.block();
+
+        let mut catch_name = None;
+        let mut catch_body = None;
+        if self.matches(&[TokenType::Catch]) {
+            self.consume(TokenType::LeftParen, "Expect '(' after 'catch'.")
+                .ok();
+            catch_name = Some(
+                self.consume(TokenType::Identifier, "Expect exception variable name.")
+                    .unwrap(),
+            );
+            self.consume(TokenType::RightParen, "Expect ')' after catch variable.")
+                .ok();
+            self.consume(TokenType::LeftBrace, "Expect '{' before catch body.")
+                .ok();
+            catch_body = Some(self.block());
+        }
+
+        let finally_body = if self.matches(&[TokenType::Finally]) {
+            self.consume(TokenType::LeftBrace, "Expect '{' after 'finally'.")
+                .ok();
+            Some(self.block())
+        } else {
+            None
+        };
+
+        if catch_body.is_none() && finally_body.is_none() {
+            self.error(&keyword, "Expect 'catch' or 'finally' after try block.");
+        }
+
+        Try::new(keyword, body, catch_name, catch_body, finally_body)
+    }
+
     fn while_statement(&mut self) -> Box<dyn Stmt> {
         self.consume(TokenType::LeftParen, "Expect '(' after while.")
             .ok();
@@ -249,16 +514,66 @@ impl Parser {
 
     // Expressions
     fn assignment(&mut self) -> BoxedExpr {
-        let expr = self.or();
+        let expr = self.conditional();
 
         if self.matches(&[TokenType::Equal]) {
-            let _equals = self.previous();
+            let equals = self.previous();
             let value = self.assignment();
 
+            if let Some(pattern) = self.assignment_pattern(&expr) {
+                return AssignPattern::new(pattern, value);
+            }
+
             if let Some(variable_expr) = expr.as_any().downcast_ref::<Variable>() {
                 let name = variable_expr.name();
                 return Assign::new(name, value);
+            } else if let Some(get_expr) = expr.as_any().downcast_ref::<Get>() {
+                return Set::new(get_expr.object(), get_expr.name(), value);
+            } else if let Some(subscript_expr) = expr.as_any().downcast_ref::<Subscript>() {
+                return SubscriptSet::new(
+                    subscript_expr.object(),
+                    subscript_expr.bracket(),
+                    subscript_expr.index(),
+                    value,
+                );
             }
+            self.error(&equals, "Invalid assignment target.");
+        } else if self.matches(&[
+            TokenType::PlusEqual,
+            TokenType::MinusEqual,
+            TokenType::StarEqual,
+            TokenType::SlashEqual,
+            TokenType::PercentEqual,
+        ]) {
+            let operator = self.previous();
+            let value = self.assignment();
+            return self.update(expr, operator, value, true);
+        }
+
+        expr
+    }
+
+    fn conditional(&mut self) -> BoxedExpr {
+        let condition = self.nil_coalescing();
+
+        if self.matches(&[TokenType::Question]) {
+            let then_branch = self.assignment();
+            self.consume(TokenType::Colon, "Expect ':' after then branch of '?'.")
+                .ok();
+            let else_branch = self.conditional();
+            return Conditional::new(condition, then_branch, else_branch);
+        }
+
+        condition
+    }
+
+    fn nil_coalescing(&mut self) -> BoxedExpr {
+        let mut expr = self.or();
+
+        while self.matches(&[TokenType::QuestionQuestion]) {
+            let operator = self.previous();
+            let right = self.or();
+            expr = Logical::new(expr, operator, right);
         }
 
         expr
@@ -304,7 +619,7 @@ impl Parser {
     }
 
     fn comparison(&mut self) -> BoxedExpr {
-        let mut expr = self.addition();
+        let mut expr = self.range();
 
         while self.matches(&[
             TokenType::Greater,
@@ -312,6 +627,70 @@ impl Parser {
             TokenType::LessEqual,
             TokenType::Less,
         ]) {
+            let operator = self.previous();
+            let right = self.range();
+            expr = Binary::new(expr, operator, right);
+        }
+        expr
+    }
+
+    fn range(&mut self) -> BoxedExpr {
+        let expr = self.bit_or();
+
+        if self.matches(&[TokenType::DotDot, TokenType::DotDotEqual]) {
+            let operator = self.previous();
+            let end = self.bit_or();
+            // `step` is only special right after a range, so it still works as a name
+            let step = if self.peek().map(|token| token.lexeme()) == Some("step".to_string())
+                && self.check(TokenType::Identifier)
+            {
+                self.next();
+                Some(self.bit_or())
+            } else {
+                None
+            };
+            return Range::new(expr, operator, end, step);
+        }
+        expr
+    }
+
+    fn bit_or(&mut self) -> BoxedExpr {
+        let mut expr = self.bit_xor();
+
+        while self.matches(&[TokenType::Pipe]) {
+            let operator = self.previous();
+            let right = self.bit_xor();
+            expr = Binary::new(expr, operator, right);
+        }
+        expr
+    }
+
+    fn bit_xor(&mut self) -> BoxedExpr {
+        let mut expr = self.bit_and();
+
+        while self.matches(&[TokenType::Caret]) {
+            let operator = self.previous();
+            let right = self.bit_and();
+            expr = Binary::new(expr, operator, right);
+        }
+        expr
+    }
+
+    fn bit_and(&mut self) -> BoxedExpr {
+        let mut expr = self.shift();
+
+        while self.matches(&[TokenType::Ampersand]) {
+            let operator = self.previous();
+            let right = self.shift();
+            expr = Binary::new(expr, operator, right);
+        }
+        expr
+    }
+
+    fn shift(&mut self) -> BoxedExpr {
+        let mut expr = self.addition();
+
+        while self.matches(&[TokenType::LessLess, TokenType::GreaterGreater]) {
             let operator = self.previous();
             let right = self.addition();
             expr = Binary::new(expr, operator, right);
@@ -333,7 +712,12 @@ impl Parser {
     fn multiplication(&mut self) -> BoxedExpr {
         let mut expr = self.unary();
 
-        while self.matches(&[TokenType::Slash, TokenType::Star]) {
+        while self.matches(&[
+            TokenType::Slash,
+            TokenType::Star,
+            TokenType::Percent,
+            TokenType::TildeSlash,
+        ]) {
             let operator = self.previous();
             let right = self.unary();
             expr = Binary::new(expr, operator, right);
@@ -342,30 +726,78 @@ impl Parser {
     }
 
     fn unary(&mut self) -> BoxedExpr {
-        if self.matches(&[TokenType::Bang, TokenType::Minus]) {
+        if self.matches(&[TokenType::Bang, TokenType::Minus, TokenType::Tilde]) {
             let operator = self.previous();
             let right = self.unary();
             return Unary::new(operator, right);
         }
+        if self.matches(&[TokenType::PlusPlus, TokenType::MinusMinus]) {
+            let operator = self.previous();
+            let target = self.unary();
+            return self.update(target, operator, Literal::new(LoxValue::Integer(1)), true);
+        }
+
+        self.exponent()
+    }
+
+    // `**` binds tighter than a unary operator on its left and groups to the right
+    fn exponent(&mut self) -> BoxedExpr {
+        let expr = self.postfix();
+
+        if self.matches(&[TokenType::StarStar]) {
+            let operator = self.previous();
+            let right = self.unary();
+            return Binary::new(expr, operator, right);
+        }
+        expr
+    }
+
+    fn postfix(&mut self) -> BoxedExpr {
+        let expr = self.call();
 
-        self.call()
+        if self.matches(&[TokenType::PlusPlus, TokenType::MinusMinus]) {
+            let operator = self.previous();
+            return self.update(expr, operator, Literal::new(LoxValue::Integer(1)), false);
+        }
+        expr
     }
 
     fn call(&mut self) -> BoxedExpr {
         let mut expr = self.primary();
+        let mut optional = false;
 
         loop {
             if self.matches(&[TokenType::LeftParen]) {
-                expr = self.finish_call(expr);
+                expr = self.finish_call(expr, false);
+            } else if self.matches(&[TokenType::Dot]) {
+                let name = self
+                    .consume(TokenType::Identifier, "Expect property name after '.'.")
+                    .unwrap();
+                expr = Get::new(expr, name, false);
+            } else if self.matches(&[TokenType::QuestionDot]) {
+                optional = true;
+                if self.matches(&[TokenType::LeftParen]) {
+                    expr = self.finish_call(expr, true);
+                } else {
+                    let name = self
+                        .consume(TokenType::Identifier, "Expect property name after '?.'.")
+                        .unwrap();
+                    expr = Get::new(expr, name, true);
+                }
+            } else if self.matches(&[TokenType::LeftBracket]) {
+                expr = self.finish_subscript(expr);
             } else {
                 break;
             }
         }
 
+        if optional {
+            return OptionalChain::new(expr);
+        }
         expr
     }
 
-    fn finish_call(&mut self, callee: BoxedExpr) -> BoxedExpr {
+    fn finish_call(&mut self, callee: BoxedExpr, optional: bool) -> BoxedExpr {
         let mut arguments: Vec<BoxedExpr> = Vec::new();
 
         if !self.check(TokenType::RightParen) {
@@ -385,7 +817,32 @@ impl Parser {
             .consume(TokenType::RightParen, "Expect ')' after arguments")
             .unwrap();
 
-        Call::new(callee, paren, arguments)
+        Call::new(callee, paren, arguments, optional)
+    }
+
+    fn finish_subscript(&mut self, object: BoxedExpr) -> BoxedExpr {
+        let bracket = self.previous();
+
+        let start = if self.check(TokenType::Colon) {
+            None
+        } else {
+            Some(self.expression())
+        };
+
+        if self.matches(&[TokenType::Colon]) {
+            let end = if self.check(TokenType::RightBracket) {
+                None
+            } else {
+                Some(self.expression())
+            };
+            self.consume(TokenType::RightBracket, "Expect ']' after slice.")
+                .ok();
+            return Slice::new(object, bracket, start, end);
+        }
+
+        self.consume(TokenType::RightBracket, "Expect ']' after index.")
+            .ok();
+        Subscript::new(object, bracket, start.unwrap())
     }
 
     fn primary(&mut self) -> BoxedExpr {
@@ -402,22 +859,153 @@ impl Parser {
         }
 
         if self.matches(&[TokenType::Number]) {
-            return Literal::new(LoxValue::Number(
-                f32::from_str(&self.previous().lexeme()).unwrap(),
-            ));
+            let lexeme = self.previous().lexeme().replace('_', "");
+            if let Some(decimal) = lexeme.strip_suffix('d') {
+                return Literal::new(LoxValue::Decimal(lox_decimal::parse(decimal).unwrap()));
+            }
+
+            let radix = match lexeme.get(..2).map(|prefix| prefix.to_lowercase()) {
+                Some(ref prefix) if prefix == "0x" => 16,
+                Some(ref prefix) if prefix == "0o" => 8,
+                Some(ref prefix) if prefix == "0b" => 2,
+                _ => 10,
+            };
+            if radix != 10 {
+                let digits = &lexeme.as_bytes()[2..];
+                return Literal::new(LoxValue::integer(
+                    BigInt::parse_bytes(digits, radix).unwrap(),
+                ));
+            }
+
+            return match BigInt::from_str(&lexeme) {
+                Ok(value) => Literal::new(LoxValue::integer(value)),
+                Err(_) => Literal::new(LoxValue::Number(f64::from_str(&lexeme).unwrap())),
+            };
         }
 
         if self.matches(&[TokenType::String]) {
             return Literal::new(LoxValue::String(self.previous().lexeme()));
         }
 
+        if self.matches(&[TokenType::Interpolation]) {
+            let mut parts: Vec<BoxedExpr> = Vec::new();
+            loop {
+                let part = self.previous();
+                parts.push(Literal::new(LoxValue::String(part.lexeme())));
+                parts.push(self.expression());
+
+                // Skip whatever is left of the embedded code up to the next part of the string
+                if !self.check(TokenType::String) && !self.check(TokenType::Interpolation) {
+                    self.error(&part, "Expect '}' after interpolated expression.");
+                    while !self.is_end()
+                        && !self.check(TokenType::String)
+                        && !self.check(TokenType::Interpolation)
+                    {
+                        self.next();
+                    }
+                }
+
+                if !self.matches(&[TokenType::Interpolation]) {
+                    break;
+                }
+            }
+
+            if let Ok(tail) = self.consume(TokenType::String, "Expect end of string interpolation.")
+            {
+                parts.push(Literal::new(LoxValue::String(tail.lexeme())));
+            }
+            return Interpolation::new(parts);
+        }
+
+        if self.matches(&[TokenType::Fun]) {
+            let name = Token::new(
+                "anonymous".to_string(),
+                TokenType::Fun,
+                self.previous().line(),
+            );
+            return Lambda::new(*self.function_body(name, "function", None));
+        }
+
+        if self.check(TokenType::LeftParen) && self.is_arrow_function() {
+            return self.arrow_function();
+        }
+
         if self.matches(&[TokenType::LeftParen]) {
+            let paren = self.previous();
             let expr = self.expression();
+
+            // a comma turns the parentheses into a tuple, `(x,)` has just one element
+            if self.matches(&[TokenType::Comma]) {
+                let mut elements = vec![expr];
+                while !self.check(TokenType::RightParen) && !self.is_end() {
+                    elements.push(self.expression());
+                    if !self.matches(&[TokenType::Comma]) {
+                        break;
+                    }
+                }
+                self.consume(TokenType::RightParen, "Expect ')' after tuple elements.")
+                    .ok();
+                return Tuple::new(paren, elements);
+            }
+
             self.consume(TokenType::RightParen, "Expect ')' after expression")
                 .ok();
             return Grouping::new(expr);
         }
 
+        if self.matches(&[TokenType::LeftBracket]) {
+            let bracket = self.previous();
+            let mut elements: Vec<BoxedExpr> = Vec::new();
+
+            if !self.check(TokenType::RightBracket) {
+                loop {
+                    elements.push(self.expression());
+
+                    if !self.matches(&[TokenType::Comma]) || self.check(TokenType::RightBracket) {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RightBracket, "Expect ']' after list elements.")
+                .ok();
+            return List::new(bracket, elements);
+        }
+
+        if self.matches(&[TokenType::LeftBrace]) {
+            let brace = self.previous();
+            let mut entries: Vec<(BoxedExpr, BoxedExpr)> = Vec::new();
+
+            if !self.check(TokenType::RightBrace) {
+                loop {
+                    let key = self.expression();
+                    self.consume(TokenType::Colon, "Expect ':' after map key.")
+                        .ok();
+                    entries.push((key, self.expression()));
+
+                    if !self.matches(&[TokenType::Comma]) || self.check(TokenType::RightBrace) {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RightBrace, "Expect '}' after map entries.")
+                .ok();
+            return Map::new(brace, entries);
+        }
+
+        if self.matches(&[TokenType::Super]) {
+            let keyword = self.previous();
+            self.consume(TokenType::Dot, "Expect '.' after 'super'.")
+                .ok();
+            let method = self
+                .consume(TokenType::Identifier, "Expect superclass method name.")
+                .unwrap();
+            return Super::new(keyword, method);
+        }
+
+        if self.matches(&[TokenType::This]) {
+            return This::new(self.previous());
+        }
+
         if self.matches(&[TokenType::Identifier]) {
             return Variable::new(self.previous());
         }
@@ -425,7 +1013,118 @@ impl Parser {
         Literal::new(LoxValue::Number(0.0))
     }
 
+    fn arrow_function(&mut self) -> BoxedExpr {
+        let name = self
+            .consume(TokenType::LeftParen, "Expect '(' before parameters")
+            .unwrap();
+        let parameters = self.parameters();
+        self.consume(TokenType::RightParen, "Expect ')' after parameters")
+            .ok();
+        let arrow = self
+            .consume(TokenType::Arrow, "Expect '=>' after parameters")
+            .unwrap();
+
+        let enclosing = mem::replace(&mut self.yields, false);
+        let body = if self.matches(&[TokenType::LeftBrace]) {
+            self.block()
+        } else {
+            vec![Return::new(arrow, Some(self.expression()))]
+        };
+        let generator = mem::replace(&mut self.yields, enclosing);
+
+        let name = Token::new("anonymous".to_string(), TokenType::Fun, name.line());
+        Lambda::new(*Function::new(name, parameters, body, generator))
+    }
+
     // helper methods not part of the parsing grammar
+    fn assignment_pattern(&mut self, expr: &BoxedExpr) -> Option<Pattern> {
+        let (bracket, elements) = if let Some(tuple) = expr.as_any().downcast_ref::<Tuple>() {
+            (tuple.paren(), tuple.elements())
+        } else if let Some(list) = expr.as_any().downcast_ref::<List>() {
+            (list.bracket(), list.elements())
+        } else {
+            return None;
+        };
+
+        let mut patterns = Vec::new();
+        for element in elements {
+            let target = element.as_any();
+            if target.is::<Variable>() || target.is::<Get>() || target.is::<Subscript>() {
+                patterns.push(Pattern::Target(element.clone()));
+            } else if let Some(pattern) = self.assignment_pattern(&element) {
+                patterns.push(pattern);
+            } else {
+                self.error(
+                    &bracket,
+                    &format!("Invalid assignment target '{}'.", element),
+                );
+            }
+        }
+        Some(Pattern::Sequence(bracket, patterns))
+    }
+
+    fn update(
+        &mut self,
+        target: BoxedExpr,
+        operator: Token,
+        value: BoxedExpr,
+        prefix: bool,
+    ) -> BoxedExpr {
+        let target_any = target.as_any();
+        if !(target_any.is::<Variable>() || target_any.is::<Get>() || target_any.is::<Subscript>())
+        {
+            self.error(
+                &operator,
+                &format!("Invalid target for '{}'.", operator.lexeme()),
+            );
+            return target;
+        }
+        Update::new(target, operator, value, prefix)
+    }
+
+    // Looks past the loop variable or pattern for an `in` before the first `;`
+    fn is_for_in(&mut self) -> bool {
+        let mut depth = 0;
+        for token in &self.tokens[self.index..] {
+            match token.token_type() {
+                TokenType::LeftParen | TokenType::LeftBracket | TokenType::LeftBrace => depth += 1,
+                TokenType::RightParen | TokenType::RightBracket | TokenType::RightBrace => {
+                    if depth == 0 {
+                        return false;
+                    }
+                    depth -= 1;
+                }
+                TokenType::In if depth == 0 => return true,
+                TokenType::Semicolon | TokenType::Eof => return false,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    fn is_arrow_function(&mut self) -> bool {
+        let mut index = self.index + 1;
+        loop {
+            match self.tokens.get(index).map(|token| token.token_type()) {
+                Some(TokenType::Identifier) | Some(TokenType::Comma) => index += 1,
+                Some(TokenType::RightParen) => break,
+                _ => return false,
+            }
+        }
+
+        match self.tokens.get(index + 1) {
+            Some(token) => token.token_type() == TokenType::Arrow,
+            None => false,
+        }
+    }
+
+    fn check_next(&mut self, token_type: TokenType) -> bool {
+        match self.tokens.get(self.index + 1) {
+            Some(token) => token.token_type() == token_type,
+            None => false,
+        }
+    }
+
     fn previous(&mut self) -> Token {
         self.tokens[self.index - 1].clone()
     }
@@ -435,7 +1134,19 @@ impl Parser {
             let token = self.next().unwrap();
             Ok(token)
         } else {
-            Err(ParserError::new(error.to_string()))
+            Err(ParserError::new(self.line(), error.to_string()))
+        }
+    }
+
+    fn error(&mut self, token: &Token, message: &str) {
+        let error = ParserError::new(token.line(), message.to_string());
+        self.errors.push(error);
+    }
+
+    fn line(&mut self) -> u32 {
+        match self.peek().or_else(|| self.tokens.last().cloned()) {
+            Some(token) => token.line(),
+            None => 0,
         }
     }
 
@@ -481,6 +1192,7 @@ impl Parser {
 
             match token.token_type() {
                 TokenType::Class => return,
+                TokenType::Const => return,
                 TokenType::Fun => return,
                 TokenType::For => return,
                 TokenType::If => return,
@@ -488,6 +1200,7 @@ impl Parser {
                 TokenType::While => return,
                 TokenType::Print => return,
                 TokenType::Return => return,
+                TokenType::Yield => return,
                 _ => (),
             }
 
@@ -511,13 +1224,18 @@ impl Iterator for Parser {
 }
 
 #[derive(Debug)]
-struct ParserError {
+pub struct ParserError {
+    line: u32,
     description: String,
 }
 
 impl ParserError {
-    fn new(description: String) -> ParserError {
-        ParserError { description }
+    fn new(line: u32, description: String) -> ParserError {
+        ParserError { line, description }
+    }
+
+    pub fn line(&self) -> u32 {
+        self.line
     }
 }
 
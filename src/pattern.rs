@@ -0,0 +1,53 @@
+use std::fmt::Display;
+
+use crate::expr::{BoxedExpr, Variable};
+use crate::token::Token;
+
+// Destructuring patterns shared by `var` declarations and assignments
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    // A variable name, or for assignments any assignable expression
+    Target(BoxedExpr),
+    // `...rest` collects whatever a sequence pattern doesn't match into a list
+    Rest(BoxedExpr),
+    // `(a, b)` or `[a, b]`, matching tuples and lists by position
+    Sequence(Token, Vec<Pattern>),
+    // `{name, age}`, matching map keys or instance fields by name
+    Fields(Token, Vec<Token>),
+}
+
+impl Pattern {
+    // Every variable a declaration binds, in source order
+    pub fn names(&self) -> Vec<Token> {
+        match self {
+            Pattern::Target(target) | Pattern::Rest(target) => target
+                .as_any()
+                .downcast_ref::<Variable>()
+                .map(|variable| vec![variable.name()])
+                .unwrap_or_default(),
+            Pattern::Sequence(_, patterns) => patterns.iter().flat_map(Pattern::names).collect(),
+            Pattern::Fields(_, names) => names.clone(),
+        }
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Pattern::Target(target) => write!(f, "{}", target),
+            Pattern::Rest(target) => write!(f, "...{}", target),
+            Pattern::Sequence(bracket, patterns) => {
+                let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+                if bracket.lexeme() == "(" {
+                    write!(f, "({})", patterns.join(", "))
+                } else {
+                    write!(f, "[{}]", patterns.join(", "))
+                }
+            }
+            Pattern::Fields(_, names) => {
+                let names: Vec<String> = names.iter().map(|name| name.lexeme()).collect();
+                write!(f, "{{{}}}", names.join(", "))
+            }
+        }
+    }
+}
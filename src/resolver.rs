@@ -1,21 +1,45 @@
 use crate::expr::{
-    Assign, Binary, BoxedExpr, Call, Grouping, Literal, Logical, Unary, Variable,
-    Visitor as ExprVisitor,
+    Assign, AssignPattern, Binary, BoxedExpr, Call, Conditional, Get, Grouping, Interpolation,
+    Lambda, List, Literal, Logical, Map, OptionalChain, Range, Set, Slice, Subscript, SubscriptSet,
+    Super, This, Tuple, Unary, Update, Variable, Visitor as ExprVisitor,
 };
 use crate::interpreter::Interpreter;
 use crate::lox_error::LoxError;
 use crate::lox_value::LoxValue;
+use crate::pattern::Pattern;
 use crate::stmt::{
-    Block, BoxedStmt, Expression, Function, If, Print, Return, Var, Visitor as StmtVisitor, While,
+    Block, BoxedStmt, Break, Class, Continue, Expression, ForIn, Function, If, Print, Return,
+    StmtResult, Throw, Try, Var, VarPattern, Visitor as StmtVisitor, While, Yield,
 };
 use crate::token::Token;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FunctionType {
+    None,
+    Function,
+    Initializer,
+    Method,
+    Generator,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ClassType {
+    None,
+    Class,
+    Subclass,
+}
+
 pub struct Resolver<'a> {
     interpreter: &'a mut Interpreter,
     scopes: Vec<Rc<RefCell<HashMap<String, bool>>>>,
+    // names declared with `const`, kept in step with `scopes`
+    constants: Vec<HashSet<String>>,
+    current_function: FunctionType,
+    current_class: ClassType,
+    loop_depth: usize,
 }
 
 impl<'a> Resolver<'a> {
@@ -23,31 +47,66 @@ impl<'a> Resolver<'a> {
         Resolver {
             interpreter,
             scopes: Vec::new(),
+            constants: Vec::new(),
+            current_function: FunctionType::None,
+            current_class: ClassType::None,
+            loop_depth: 0,
         }
     }
 
     pub fn resolve(&mut self, statments: &Vec<BoxedStmt>) -> Result<(), LoxError> {
         for statement in statments {
-            self.resolve_stmt(statement);
+            self.resolve_stmt(statement)?;
         }
         Ok(())
     }
 
     fn resolve_stmt(&mut self, stmt: &BoxedStmt) -> Result<(), LoxError> {
-        stmt.accept(self);
+        stmt.accept(self)?;
         Ok(())
     }
 
-    fn resolve_expr(&mut self, expr: &BoxedExpr) {
-        expr.accept(self);
+    fn resolve_expr(&mut self, expr: &BoxedExpr) -> Result<(), LoxError> {
+        expr.accept(self)?;
+        Ok(())
+    }
+
+    fn resolve_function(
+        &mut self,
+        function: &Function,
+        function_type: FunctionType,
+    ) -> Result<(), LoxError> {
+        let enclosing_function = self.current_function;
+        let enclosing_loop_depth = self.loop_depth;
+        self.current_function =
+            if function.generator() && function_type != FunctionType::Initializer {
+                FunctionType::Generator
+            } else {
+                function_type
+            };
+        self.loop_depth = 0;
+
+        self.begin_scope();
+        for param in function.params() {
+            self.declare(param.clone());
+            self.define(param);
+        }
+        self.resolve(&function.body())?;
+        self.end_scope();
+
+        self.current_function = enclosing_function;
+        self.loop_depth = enclosing_loop_depth;
+        Ok(())
     }
 
     fn begin_scope(&mut self) {
-        self.scopes.push(Rc::new(RefCell::new(HashMap::new())))
+        self.scopes.push(Rc::new(RefCell::new(HashMap::new())));
+        self.constants.push(HashSet::new());
     }
 
     fn end_scope(&mut self) {
         self.scopes.pop();
+        self.constants.pop();
     }
 
     fn declare(&mut self, name: Token) {
@@ -59,6 +118,9 @@ impl<'a> Resolver<'a> {
             let mut scope = scope_ref.borrow_mut();
             scope.insert(name.lexeme(), false);
         }
+        if let Some(constants) = self.constants.last_mut() {
+            constants.remove(&name.lexeme());
+        }
     }
 
     fn define(&mut self, name: Token) {
@@ -72,11 +134,61 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    // Only `const` can redeclare a local constant, globals are checked when the declaration runs
+    fn check_redeclarable(&self, name: &Token) -> Result<(), LoxError> {
+        if let Some(constants) = self.constants.last() {
+            if constants.contains(&name.lexeme()) {
+                return Err(LoxError::BindingError(
+                    name.lexeme(),
+                    "Cannot redeclare a constant.".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // Local constants are caught here, globals are checked when the assignment runs
+    fn check_assignable(&self, name: Token) -> Result<(), LoxError> {
+        let lexeme = name.lexeme();
+        for (scope_ref, constants) in self.scopes.iter().zip(&self.constants).rev() {
+            if scope_ref.borrow().contains_key(&lexeme) {
+                if constants.contains(&lexeme) {
+                    return Err(LoxError::BindingError(
+                        lexeme,
+                        "Cannot assign to a constant.".to_string(),
+                    ));
+                }
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    // Assignment targets in a pattern resolve like the left-hand side of `=`
+    fn resolve_pattern(&mut self, pattern: &Pattern) -> Result<LoxValue, LoxError> {
+        match pattern {
+            Pattern::Target(target) | Pattern::Rest(target) => {
+                if let Some(variable) = target.as_any().downcast_ref::<Variable>() {
+                    self.check_assignable(variable.name())?;
+                }
+                self.resolve_expr(target)?;
+            }
+            Pattern::Sequence(_, patterns) => {
+                for pattern in patterns {
+                    self.resolve_pattern(pattern)?;
+                }
+            }
+            Pattern::Fields(..) => {}
+        }
+        Ok(LoxValue::Nil)
+    }
+
     fn resolve_local(&mut self, expr: BoxedExpr, name: String) {
         for (index, scope_ref) in self.scopes.iter().rev().enumerate() {
             let scope = scope_ref.borrow();
             if scope.contains_key(&name) {
                 self.interpreter.resolve(expr.clone(), index);
+                return;
             }
         }
     }
@@ -104,29 +216,84 @@ impl<'a> ExprVisitor for Resolver<'a> {
         Ok(LoxValue::Nil)
     }
 
-    fn visit_logical(&mut self, _expr: &Logical) -> Result<Self::Value, LoxError> {
+    fn visit_logical(&mut self, expr: &Logical) -> Result<Self::Value, LoxError> {
+        self.resolve_expr(&expr.left())?;
+        self.resolve_expr(&expr.right())?;
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_call(&mut self, expr: &Call) -> Result<Self::Value, LoxError> {
+        self.resolve_expr(&expr.callee())?;
+        for argument in expr.arguments() {
+            self.resolve_expr(&argument)?;
+        }
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_get(&mut self, expr: &Get) -> Result<Self::Value, LoxError> {
+        self.resolve_expr(&expr.object())?;
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_set(&mut self, expr: &Set) -> Result<Self::Value, LoxError> {
+        self.resolve_expr(&expr.value())?;
+        self.resolve_expr(&expr.object())?;
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_this(&mut self, expr: &This) -> Result<Self::Value, LoxError> {
+        if self.current_class == ClassType::None {
+            return Err(LoxError::BindingError(
+                expr.keyword().lexeme(),
+                "Cannot use 'this' outside of a class.".to_string(),
+            ));
+        }
+
+        self.resolve_local(Box::new(expr.clone()), expr.keyword().lexeme());
         Ok(LoxValue::Nil)
     }
 
-    fn visit_call(&mut self, _expr: &Call) -> Result<Self::Value, LoxError> {
+    fn visit_super(&mut self, expr: &Super) -> Result<Self::Value, LoxError> {
+        match self.current_class {
+            ClassType::None => {
+                return Err(LoxError::BindingError(
+                    expr.keyword().lexeme(),
+                    "Cannot use 'super' outside of a class.".to_string(),
+                ))
+            }
+            ClassType::Class => {
+                return Err(LoxError::BindingError(
+                    expr.keyword().lexeme(),
+                    "Cannot use 'super' in a class with no superclass.".to_string(),
+                ))
+            }
+            ClassType::Subclass => {}
+        }
+
+        self.resolve_local(Box::new(expr.clone()), expr.keyword().lexeme());
         Ok(LoxValue::Nil)
     }
 
-    fn visit_unary(&mut self, _expr: &Unary) -> Result<Self::Value, LoxError> {
+    fn visit_unary(&mut self, expr: &Unary) -> Result<Self::Value, LoxError> {
+        self.resolve_expr(&expr.right())?;
         Ok(LoxValue::Nil)
     }
 
-    fn visit_binary(&mut self, _expr: &Binary) -> Result<Self::Value, LoxError> {
+    fn visit_binary(&mut self, expr: &Binary) -> Result<Self::Value, LoxError> {
+        self.resolve_expr(&expr.left())?;
+        self.resolve_expr(&expr.right())?;
         Ok(LoxValue::Nil)
     }
 
-    fn visit_grouping(&mut self, _expr: &Grouping) -> Result<Self::Value, LoxError> {
+    fn visit_grouping(&mut self, expr: &Grouping) -> Result<Self::Value, LoxError> {
+        self.resolve_expr(&expr.expression())?;
         Ok(LoxValue::Nil)
     }
 
     fn visit_variable(&mut self, expr: &Variable) -> Result<Self::Value, LoxError> {
         let name = expr.name().lexeme();
-        if self.scopes.len() == 0 && self.scopes.last().unwrap().borrow().get(&name) == Some(&false)
+        if !self.scopes.is_empty()
+            && self.scopes.last().unwrap().borrow().get(&name) == Some(&false)
         {
             let error = LoxError::BindingError(
                 name,
@@ -140,51 +307,364 @@ impl<'a> ExprVisitor for Resolver<'a> {
         Ok(LoxValue::Nil)
     }
 
-    fn visit_assignment(&mut self, _expr: &Assign) -> Result<Self::Value, LoxError> {
+    fn visit_lambda(&mut self, expr: &Lambda) -> Result<Self::Value, LoxError> {
+        self.resolve_function(&expr.declaration(), FunctionType::Function)?;
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_assignment(&mut self, expr: &Assign) -> Result<Self::Value, LoxError> {
+        self.check_assignable(expr.name())?;
+        self.resolve_expr(&expr.value())?;
+        self.resolve_local(Box::new(expr.clone()), expr.name().lexeme());
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_list(&mut self, expr: &List) -> Result<Self::Value, LoxError> {
+        for element in expr.elements() {
+            self.resolve_expr(&element)?;
+        }
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_subscript(&mut self, expr: &Subscript) -> Result<Self::Value, LoxError> {
+        self.resolve_expr(&expr.object())?;
+        self.resolve_expr(&expr.index())?;
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_conditional(&mut self, expr: &Conditional) -> Result<Self::Value, LoxError> {
+        self.resolve_expr(&expr.condition())?;
+        self.resolve_expr(&expr.then_branch())?;
+        self.resolve_expr(&expr.else_branch())?;
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_optional_chain(&mut self, expr: &OptionalChain) -> Result<Self::Value, LoxError> {
+        self.resolve_expr(&expr.expression())?;
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_tuple(&mut self, expr: &Tuple) -> Result<Self::Value, LoxError> {
+        for element in expr.elements() {
+            self.resolve_expr(&element)?;
+        }
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_assign_pattern(&mut self, expr: &AssignPattern) -> Result<Self::Value, LoxError> {
+        self.resolve_expr(&expr.value())?;
+        self.resolve_pattern(&expr.pattern())
+    }
+
+    fn visit_range(&mut self, expr: &Range) -> Result<Self::Value, LoxError> {
+        self.resolve_expr(&expr.start())?;
+        self.resolve_expr(&expr.end())?;
+        if let Some(step) = expr.step() {
+            self.resolve_expr(&step)?;
+        }
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_update(&mut self, expr: &Update) -> Result<Self::Value, LoxError> {
+        if let Some(variable) = expr.target().as_any().downcast_ref::<Variable>() {
+            self.check_assignable(variable.name())?;
+        }
+        self.resolve_expr(&expr.value())?;
+        self.resolve_expr(&expr.target())?;
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_subscript_set(&mut self, expr: &SubscriptSet) -> Result<Self::Value, LoxError> {
+        self.resolve_expr(&expr.value())?;
+        self.resolve_expr(&expr.object())?;
+        self.resolve_expr(&expr.index())?;
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_slice(&mut self, expr: &Slice) -> Result<Self::Value, LoxError> {
+        self.resolve_expr(&expr.object())?;
+        if let Some(start) = expr.start() {
+            self.resolve_expr(&start)?;
+        }
+        if let Some(end) = expr.end() {
+            self.resolve_expr(&end)?;
+        }
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_interpolation(&mut self, expr: &Interpolation) -> Result<Self::Value, LoxError> {
+        for part in expr.parts() {
+            self.resolve_expr(&part)?;
+        }
+        Ok(LoxValue::Nil)
+    }
+
+    fn visit_map(&mut self, expr: &Map) -> Result<Self::Value, LoxError> {
+        for (key, value) in expr.entries() {
+            self.resolve_expr(&key)?;
+            self.resolve_expr(&value)?;
+        }
         Ok(LoxValue::Nil)
     }
 }
 
 impl<'a> StmtVisitor for Resolver<'a> {
-    type Value = Option<LoxValue>;
+    type Value = StmtResult;
 
-    fn visit_expression_statement(&mut self, _stmt: &Expression) -> Option<LoxValue> {
-        None
+    fn visit_expression_statement(&mut self, stmt: &Expression) -> StmtResult {
+        self.resolve_expr(&stmt.clone().expr())?;
+        Ok(None)
     }
 
-    fn visit_if_statement(&mut self, _stmt: &If) -> Option<LoxValue> {
-        None
+    fn visit_if_statement(&mut self, stmt: &If) -> StmtResult {
+        self.resolve_expr(&stmt.condition())?;
+        self.resolve_stmt(&stmt.then_branch())?;
+        if let Some(else_branch) = stmt.else_branch() {
+            self.resolve_stmt(&else_branch)?;
+        }
+        Ok(None)
     }
 
-    fn visit_print_statement(&mut self, _stmt: &Print) -> Option<LoxValue> {
-        None
+    fn visit_print_statement(&mut self, stmt: &Print) -> StmtResult {
+        self.resolve_expr(&stmt.clone().expr())?;
+        Ok(None)
     }
 
-    fn visit_return_statement(&mut self, _stmt: &Return) -> Option<LoxValue> {
-        None
+    fn visit_return_statement(&mut self, stmt: &Return) -> StmtResult {
+        if self.current_function == FunctionType::None {
+            return Err(LoxError::BindingError(
+                stmt.keyword().lexeme(),
+                "Cannot return from top-level code.".to_string(),
+            ));
+        }
+
+        if let Some(value) = stmt.value() {
+            if self.current_function == FunctionType::Initializer {
+                return Err(LoxError::BindingError(
+                    stmt.keyword().lexeme(),
+                    "Cannot return a value from an initializer.".to_string(),
+                ));
+            }
+
+            if self.current_function == FunctionType::Generator {
+                return Err(LoxError::BindingError(
+                    stmt.keyword().lexeme(),
+                    "Cannot return a value from a generator.".to_string(),
+                ));
+            }
+
+            self.resolve_expr(&value)?;
+        }
+        Ok(None)
     }
 
-    fn visit_var_statement(&mut self, stmt: &Var) -> Option<LoxValue> {
+    fn visit_yield_statement(&mut self, stmt: &Yield) -> StmtResult {
+        match self.current_function {
+            FunctionType::None => {
+                return Err(LoxError::BindingError(
+                    stmt.keyword().lexeme(),
+                    "Cannot yield from top-level code.".to_string(),
+                ))
+            }
+            FunctionType::Initializer => {
+                return Err(LoxError::BindingError(
+                    stmt.keyword().lexeme(),
+                    "Cannot yield from an initializer.".to_string(),
+                ))
+            }
+            _ => {}
+        }
+
+        if let Some(value) = stmt.value() {
+            self.resolve_expr(&value)?;
+        }
+        Ok(None)
+    }
+
+    fn visit_var_statement(&mut self, stmt: &Var) -> StmtResult {
+        if stmt.constant() && stmt.initializer().is_none() {
+            return Err(LoxError::BindingError(
+                stmt.name().lexeme(),
+                "A constant must be initialized.".to_string(),
+            ));
+        }
+
+        if !stmt.constant() {
+            self.check_redeclarable(&stmt.name())?;
+        }
         self.declare(stmt.name());
         if let Some(initializer) = stmt.initializer() {
-            self.resolve_expr(&initializer);
+            self.resolve_expr(&initializer)?;
         }
         self.define(stmt.name());
-        None
+        if stmt.constant() {
+            if let Some(constants) = self.constants.last_mut() {
+                constants.insert(stmt.name().lexeme());
+            }
+        }
+        Ok(None)
     }
 
-    fn visit_block_statement(&mut self, stmt: &Block) -> Option<LoxValue> {
+    fn visit_var_pattern_statement(&mut self, stmt: &VarPattern) -> StmtResult {
+        let names = stmt.pattern().names();
+        for name in &names {
+            if !stmt.constant() {
+                self.check_redeclarable(name)?;
+            }
+            self.declare(name.clone());
+        }
+        self.resolve_expr(&stmt.initializer())?;
+        for name in names {
+            self.define(name.clone());
+            if stmt.constant() {
+                if let Some(constants) = self.constants.last_mut() {
+                    constants.insert(name.lexeme());
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn visit_block_statement(&mut self, stmt: &Block) -> StmtResult {
         self.begin_scope();
-        self.resolve(&stmt.statements());
+        self.resolve(&stmt.statements())?;
         self.end_scope();
-        None
+        Ok(None)
     }
 
-    fn visit_while_statement(&mut self, _stmt: &While) -> Option<LoxValue> {
-        None
+    fn visit_while_statement(&mut self, stmt: &While) -> StmtResult {
+        self.resolve_expr(&stmt.condition())?;
+        if let Some(increment) = stmt.increment() {
+            self.resolve_expr(&increment)?;
+        }
+
+        self.loop_depth += 1;
+        self.resolve_stmt(&stmt.body())?;
+        self.loop_depth -= 1;
+        Ok(None)
     }
 
-    fn visit_function_statement(&mut self, _stmt: &Function) -> Option<LoxValue> {
-        None
+    fn visit_for_in_statement(&mut self, stmt: &ForIn) -> StmtResult {
+        self.resolve_expr(&stmt.iterable())?;
+
+        self.begin_scope();
+        for name in stmt.pattern().names() {
+            self.declare(name.clone());
+            self.define(name.clone());
+            if stmt.constant() {
+                if let Some(constants) = self.constants.last_mut() {
+                    constants.insert(name.lexeme());
+                }
+            }
+        }
+        self.loop_depth += 1;
+        self.resolve_stmt(&stmt.body())?;
+        self.loop_depth -= 1;
+        self.end_scope();
+        Ok(None)
+    }
+
+    fn visit_try_statement(&mut self, stmt: &Try) -> StmtResult {
+        self.begin_scope();
+        self.resolve(&stmt.body())?;
+        self.end_scope();
+
+        if let (Some(name), Some(catch_body)) = (stmt.catch_name(), stmt.catch_body()) {
+            self.begin_scope();
+            self.declare(name.clone());
+            self.define(name);
+            self.resolve(&catch_body)?;
+            self.end_scope();
+        }
+
+        if let Some(finally_body) = stmt.finally_body() {
+            self.begin_scope();
+            self.resolve(&finally_body)?;
+            self.end_scope();
+        }
+        Ok(None)
+    }
+
+    fn visit_throw_statement(&mut self, stmt: &Throw) -> StmtResult {
+        self.resolve_expr(&stmt.value())?;
+        Ok(None)
+    }
+
+    fn visit_break_statement(&mut self, stmt: &Break) -> StmtResult {
+        if self.loop_depth == 0 {
+            return Err(LoxError::BindingError(
+                stmt.keyword().lexeme(),
+                "Cannot use 'break' outside of a loop.".to_string(),
+            ));
+        }
+        Ok(None)
+    }
+
+    fn visit_continue_statement(&mut self, stmt: &Continue) -> StmtResult {
+        if self.loop_depth == 0 {
+            return Err(LoxError::BindingError(
+                stmt.keyword().lexeme(),
+                "Cannot use 'continue' outside of a loop.".to_string(),
+            ));
+        }
+        Ok(None)
+    }
+
+    fn visit_function_statement(&mut self, stmt: &Function) -> StmtResult {
+        self.check_redeclarable(&stmt.name())?;
+        self.declare(stmt.name());
+        self.define(stmt.name());
+        self.resolve_function(stmt, FunctionType::Function)?;
+        Ok(None)
+    }
+
+    fn visit_class_statement(&mut self, stmt: &Class) -> StmtResult {
+        let enclosing_class = self.current_class;
+        self.current_class = ClassType::Class;
+
+        self.check_redeclarable(&stmt.name())?;
+        self.declare(stmt.name());
+        self.define(stmt.name());
+
+        if let Some(superclass) = stmt.superclass() {
+            if superclass.name().lexeme() == stmt.name().lexeme() {
+                return Err(LoxError::BindingError(
+                    superclass.name().lexeme(),
+                    "A class cannot inherit from itself.".to_string(),
+                ));
+            }
+
+            self.current_class = ClassType::Subclass;
+            let superclass_expr: BoxedExpr = Box::new(superclass);
+            self.resolve_expr(&superclass_expr)?;
+
+            self.begin_scope();
+            if let Some(scope_ref) = self.scopes.last() {
+                scope_ref.borrow_mut().insert("super".to_string(), true);
+            }
+        }
+
+        self.begin_scope();
+        if let Some(scope_ref) = self.scopes.last() {
+            scope_ref.borrow_mut().insert("this".to_string(), true);
+        }
+
+        for method in stmt.methods() {
+            let function_type = if method.name().lexeme() == "init" {
+                FunctionType::Initializer
+            } else {
+                FunctionType::Method
+            };
+            self.resolve_function(&method, function_type)?;
+        }
+
+        self.end_scope();
+
+        if stmt.superclass().is_some() {
+            self.end_scope();
+        }
+
+        self.current_class = enclosing_class;
+        Ok(None)
     }
 }
@@ -4,27 +4,37 @@ use std::fmt;
 use std::fmt::Display;
 use std::iter::{Enumerate, Peekable};
 
+use crate::lox_decimal;
 use crate::token::{Token, TokenType};
 
 lazy_static! {
     static ref KEYWORDS: HashMap<&'static str, TokenType> = {
         let mut keywords = HashMap::new();
         keywords.insert("and", TokenType::And);
+        keywords.insert("break", TokenType::Break);
+        keywords.insert("catch", TokenType::Catch);
         keywords.insert("class", TokenType::Class);
+        keywords.insert("const", TokenType::Const);
+        keywords.insert("continue", TokenType::Continue);
         keywords.insert("else", TokenType::Else);
         keywords.insert("false", TokenType::False);
+        keywords.insert("finally", TokenType::Finally);
         keywords.insert("for", TokenType::For);
         keywords.insert("fun", TokenType::Fun);
         keywords.insert("if", TokenType::If);
+        keywords.insert("in", TokenType::In);
         keywords.insert("nil", TokenType::Nil);
         keywords.insert("or", TokenType::Or);
         keywords.insert("print", TokenType::Print);
         keywords.insert("return", TokenType::Return);
         keywords.insert("super", TokenType::Super);
         keywords.insert("this", TokenType::This);
+        keywords.insert("throw", TokenType::Throw);
         keywords.insert("true", TokenType::True);
+        keywords.insert("try", TokenType::Try);
         keywords.insert("var", TokenType::Var);
         keywords.insert("while", TokenType::While);
+        keywords.insert("yield", TokenType::Yield);
         keywords
     };
 }
@@ -43,16 +53,23 @@ impl Scanner {
     }
 
     pub fn scan(&mut self) -> Result<&Vec<Token>, ParserError> {
-        let mut line = 0;
-        let mut iter = self.source.chars().enumerate().peekable();
+        let mut line = 1;
+        let mut tokens = Vec::new();
+        scan_tokens(&self.source, &mut line, &mut tokens)?;
+        tokens.push(Token::new("".to_string(), TokenType::Eof, line));
 
-        while let Some((_i, c)) = iter.next() {
-            let scanned_token = scan_token(c, &mut line, &mut iter)?;
-            if let Some(token) = scanned_token {
+        // doc comments become trivia on whichever token follows them
+        let mut docs: Vec<String> = Vec::new();
+        for token in tokens {
+            if token.token_type() == TokenType::DocComment {
+                docs.push(token.lexeme());
+            } else if docs.is_empty() {
                 self.tokens.push(token);
+            } else {
+                self.tokens.push(token.with_doc(Some(docs.join("\n"))));
+                docs.clear();
             }
         }
-        self.tokens.push(Token::new("".to_string(), TokenType::Eof));
         Ok(&self.tokens)
     }
 }
@@ -60,12 +77,22 @@ impl Scanner {
 #[derive(Debug)]
 pub struct ParserError {
     line: u32,
-    character: char,
+    message: String,
 }
 
 impl ParserError {
     fn new(line: u32, character: char) -> ParserError {
-        ParserError { line, character }
+        ParserError::with_message(
+            line,
+            &format!("Encountered an unparseable character '{}'", character),
+        )
+    }
+
+    fn with_message(line: u32, message: &str) -> ParserError {
+        ParserError {
+            line,
+            message: message.to_string(),
+        }
     }
 
     pub fn line(&self) -> u32 {
@@ -75,22 +102,31 @@ impl ParserError {
 
 impl Error for ParserError {
     fn description(&self) -> &str {
-        "Encountered an unparseable character"
+        &self.message
     }
 }
 
 impl Display for ParserError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "Encountered an unparseable character on line {}",
-            self.line
-        )
+        write!(f, "{} on line {}", self.message, self.line)
     }
 }
 
 type ScannerIterator<'a> = Peekable<Enumerate<std::str::Chars<'a>>>;
 
+fn scan_tokens(source: &str, line: &mut u32, tokens: &mut Vec<Token>) -> Result<(), ParserError> {
+    let mut iter = source.chars().enumerate().peekable();
+
+    while let Some((_i, c)) = iter.next() {
+        if c == '"' {
+            scan_string(line, &mut iter, tokens)?;
+        } else if let Some(token) = scan_token(c, line, &mut iter)? {
+            tokens.push(token);
+        }
+    }
+    Ok(())
+}
+
 fn scan_token(
     c: char,
     line: &mut u32,
@@ -101,12 +137,94 @@ fn scan_token(
         ')' => (")".to_string(), TokenType::RightParen),
         '{' => ("{".to_string(), TokenType::LeftBrace),
         '}' => ("}".to_string(), TokenType::RightBrace),
+        '[' => ("[".to_string(), TokenType::LeftBracket),
+        ']' => ("]".to_string(), TokenType::RightBracket),
+        ':' => (":".to_string(), TokenType::Colon),
         ',' => (",".to_string(), TokenType::Comma),
-        '.' => (".".to_string(), TokenType::Dot),
-        '-' => ("-".to_string(), TokenType::Minus),
-        '+' => ("+".to_string(), TokenType::Plus),
+        '.' => {
+            if matches!(iter.peek(), Some((_, '.'))) && char_after_next(iter) == Some('.') {
+                iter.next();
+                iter.next();
+                ("...".to_string(), TokenType::Ellipsis)
+            } else if let Some((_, '.')) = iter.peek() {
+                iter.next();
+                if let Some((_, '=')) = iter.peek() {
+                    iter.next();
+                    ("..=".to_string(), TokenType::DotDotEqual)
+                } else {
+                    ("..".to_string(), TokenType::DotDot)
+                }
+            } else {
+                (".".to_string(), TokenType::Dot)
+            }
+        }
+        '-' => {
+            if let Some((_, '=')) = iter.peek() {
+                iter.next();
+                ("-=".to_string(), TokenType::MinusEqual)
+            } else if let Some((_, '-')) = iter.peek() {
+                iter.next();
+                ("--".to_string(), TokenType::MinusMinus)
+            } else {
+                ("-".to_string(), TokenType::Minus)
+            }
+        }
+        '+' => {
+            if let Some((_, '=')) = iter.peek() {
+                iter.next();
+                ("+=".to_string(), TokenType::PlusEqual)
+            } else if let Some((_, '+')) = iter.peek() {
+                iter.next();
+                ("++".to_string(), TokenType::PlusPlus)
+            } else {
+                ("+".to_string(), TokenType::Plus)
+            }
+        }
         ';' => (";".to_string(), TokenType::Semicolon),
-        '*' => ("*".to_string(), TokenType::Star),
+        '*' => {
+            if let Some((_, '*')) = iter.peek() {
+                iter.next();
+                ("**".to_string(), TokenType::StarStar)
+            } else if let Some((_, '=')) = iter.peek() {
+                iter.next();
+                ("*=".to_string(), TokenType::StarEqual)
+            } else {
+                ("*".to_string(), TokenType::Star)
+            }
+        }
+        '%' => {
+            if let Some((_, '=')) = iter.peek() {
+                iter.next();
+                ("%=".to_string(), TokenType::PercentEqual)
+            } else {
+                ("%".to_string(), TokenType::Percent)
+            }
+        }
+        '?' => {
+            if let Some((_, '?')) = iter.peek() {
+                iter.next();
+                ("??".to_string(), TokenType::QuestionQuestion)
+            } else if let Some((_, '.')) = iter.peek() {
+                iter.next();
+                ("?.".to_string(), TokenType::QuestionDot)
+            } else {
+                ("?".to_string(), TokenType::Question)
+            }
+        }
+        '&' => ("&".to_string(), TokenType::Ampersand),
+        '|' => ("|".to_string(), TokenType::Pipe),
+        '^' => ("^".to_string(), TokenType::Caret),
+        '~' => {
+            // `//` already starts a comment, so integer division is spelled `~/`
+            if matches!(iter.peek(), Some((_, '/')))
+                && !matches!(char_after_next(iter), Some('/') | Some('*'))
+            {
+                iter.next();
+                ("~/".to_string(), TokenType::TildeSlash)
+            } else {
+                ("~".to_string(), TokenType::Tilde)
+            }
+        }
         '!' => {
             if let Some((_, '=')) = iter.peek() {
                 iter.next();
@@ -119,6 +237,9 @@ fn scan_token(
             if let Some((_, '=')) = iter.peek() {
                 iter.next();
                 ("==".to_string(), TokenType::EqualEqual)
+            } else if let Some((_, '>')) = iter.peek() {
+                iter.next();
+                ("=>".to_string(), TokenType::Arrow)
             } else {
                 ("=".to_string(), TokenType::Equal)
             }
@@ -127,6 +248,9 @@ fn scan_token(
             if let Some((_, '=')) = iter.peek() {
                 iter.next();
                 ("<=".to_string(), TokenType::LessEqual)
+            } else if let Some((_, '<')) = iter.peek() {
+                iter.next();
+                ("<<".to_string(), TokenType::LessLess)
             } else {
                 ("<".to_string(), TokenType::Less)
             }
@@ -135,19 +259,42 @@ fn scan_token(
             if let Some((_, '=')) = iter.peek() {
                 iter.next();
                 (">=".to_string(), TokenType::GreaterEqual)
+            } else if let Some((_, '>')) = iter.peek() {
+                iter.next();
+                (">>".to_string(), TokenType::GreaterGreater)
             } else {
                 (">".to_string(), TokenType::Greater)
             }
         }
         '/' => {
             if let Some((_, '/')) = iter.peek() {
+                iter.next();
                 // it's a comment advance to end of line
-                while let Some((_, c)) = iter.next() {
-                    if c == '\n' {
+                let mut text = String::new();
+                while let Some((_, c)) = iter.peek() {
+                    if *c == '\n' {
                         break;
                     }
+                    text.push(*c);
+                    iter.next();
                 }
+                // `///` is a doc comment, kept so the parser can attach it to a declaration
+                if text.starts_with('/') && !text.starts_with("//") {
+                    let text = text[1..].strip_prefix(' ').unwrap_or(&text[1..]);
+                    return Ok(Some(Token::new(
+                        text.trim_end().to_string(),
+                        TokenType::DocComment,
+                        *line,
+                    )));
+                }
+                return Ok(None);
+            } else if let Some((_, '*')) = iter.peek() {
+                iter.next();
+                scan_block_comment(line, iter)?;
                 return Ok(None);
+            } else if let Some((_, '=')) = iter.peek() {
+                iter.next();
+                ("/=".to_string(), TokenType::SlashEqual)
             } else {
                 ("/".to_string(), TokenType::Slash)
             }
@@ -157,10 +304,13 @@ fn scan_token(
             *line += 1;
             return Ok(None);
         }
-        '"' => (scan_string(iter), TokenType::String),
+        'r' if matches!(iter.peek(), Some((_, '"'))) => {
+            iter.next();
+            (scan_raw_string(line, iter)?, TokenType::String)
+        }
         c => {
-            if c.is_numeric() {
-                (scan_number(c, iter), TokenType::Number)
+            if c.is_ascii_digit() {
+                (scan_number(c, *line, iter)?, TokenType::Number)
             } else if c.is_alphabetic() || c == '_' {
                 scan_identifier(c, iter)
             } else {
@@ -168,31 +318,286 @@ fn scan_token(
             }
         }
     };
-    Ok(Some(Token::new(token.to_string(), token_type)))
+    Ok(Some(Token::new(token.to_string(), token_type, *line)))
 }
 
-fn scan_string(iter: &mut ScannerIterator) -> String {
+// Block comments nest, so `/* a /* b */ c */` is a single comment
+fn scan_block_comment(line: &mut u32, iter: &mut ScannerIterator) -> Result<(), ParserError> {
+    let start = *line;
+    let mut depth = 1;
+
+    while depth > 0 {
+        match iter.next() {
+            Some((_, '/')) if matches!(iter.peek(), Some((_, '*'))) => {
+                iter.next();
+                depth += 1;
+            }
+            Some((_, '*')) if matches!(iter.peek(), Some((_, '/'))) => {
+                iter.next();
+                depth -= 1;
+            }
+            Some((_, '\n')) => *line += 1,
+            Some(_) => {}
+            None => {
+                return Err(ParserError::with_message(
+                    start,
+                    "Unterminated block comment",
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+// Each `${...}` in a string ends the preceding segment with an Interpolation token and
+// scans the embedded expression in place. The final segment is a plain String token.
+fn scan_string(
+    line: &mut u32,
+    iter: &mut ScannerIterator,
+    tokens: &mut Vec<Token>,
+) -> Result<(), ParserError> {
+    let start = *line;
     let mut string = String::new();
+    loop {
+        match iter.next() {
+            Some((_, '"')) => {
+                tokens.push(Token::new(string, TokenType::String, *line));
+                return Ok(());
+            }
+            Some((_, '$')) if matches!(iter.peek(), Some((_, '{'))) => {
+                iter.next();
+                tokens.push(Token::new(string, TokenType::Interpolation, *line));
+                string = String::new();
+
+                let code = scan_interpolation(*line, iter)?;
+                scan_tokens(&code, line, tokens)?;
+            }
+            Some((_, '\\')) => string.push(scan_escape(*line, iter)?),
+            Some((_, c)) => {
+                if c == '\n' {
+                    *line += 1;
+                }
+                string.push(c);
+            }
+            None => return Err(ParserError::with_message(start, "Unterminated string")),
+        }
+    }
+}
+
+// Collects the source of an embedded expression up to its closing brace
+fn scan_interpolation(line: u32, iter: &mut ScannerIterator) -> Result<String, ParserError> {
+    let mut code = String::new();
+    let mut depth = 1;
+    let mut in_string = false;
+
     while let Some((_, c)) = iter.next() {
-        if c == '"' {
-            break;
+        match c {
+            '"' => in_string = !in_string,
+            '\\' if in_string => {
+                code.push(c);
+                if let Some((_, escaped)) = iter.next() {
+                    code.push(escaped);
+                }
+                continue;
+            }
+            '{' if !in_string => depth += 1,
+            '}' if !in_string => {
+                depth -= 1;
+                if depth == 0 {
+                    if code.trim().is_empty() {
+                        return Err(ParserError::with_message(
+                            line,
+                            "Empty string interpolation",
+                        ));
+                    }
+                    return Ok(code);
+                }
+            }
+            _ => {}
         }
-        string.push(c);
+        code.push(c);
     }
-    string
+
+    Err(ParserError::with_message(
+        line,
+        "Unterminated string interpolation",
+    ))
+}
+
+fn scan_escape(line: u32, iter: &mut ScannerIterator) -> Result<char, ParserError> {
+    let escaped = match iter.next() {
+        Some((_, 'n')) => '\n',
+        Some((_, 't')) => '\t',
+        Some((_, 'r')) => '\r',
+        Some((_, '0')) => '\0',
+        Some((_, '"')) => '"',
+        Some((_, '$')) => '$',
+        Some((_, '\\')) => '\\',
+        Some((_, 'u')) => return scan_unicode_escape(line, iter),
+        Some((_, c)) => {
+            return Err(ParserError::with_message(
+                line,
+                &format!("Invalid escape sequence '\\{}'", c),
+            ))
+        }
+        None => return Err(ParserError::with_message(line, "Unterminated string")),
+    };
+    Ok(escaped)
+}
+
+// Escapes in the form \u{1F600}, with one to six hex digits
+fn scan_unicode_escape(line: u32, iter: &mut ScannerIterator) -> Result<char, ParserError> {
+    let invalid = || ParserError::with_message(line, "Invalid unicode escape sequence");
+
+    if iter.next().map(|(_, c)| c) != Some('{') {
+        return Err(invalid());
+    }
+
+    let mut digits = String::new();
+    loop {
+        match iter.next() {
+            Some((_, '}')) => break,
+            Some((_, c)) if c.is_ascii_hexdigit() && digits.len() < 6 => digits.push(c),
+            _ => return Err(invalid()),
+        }
+    }
+
+    u32::from_str_radix(&digits, 16)
+        .ok()
+        .and_then(std::char::from_u32)
+        .ok_or_else(invalid)
 }
 
-fn scan_number(starting_char: char, iter: &mut ScannerIterator) -> String {
+fn scan_raw_string(line: &mut u32, iter: &mut ScannerIterator) -> Result<String, ParserError> {
+    let start = *line;
+    let mut string = String::new();
+    loop {
+        match iter.next() {
+            Some((_, '"')) => return Ok(string),
+            Some((_, c)) => {
+                if c == '\n' {
+                    *line += 1;
+                }
+                string.push(c);
+            }
+            None => return Err(ParserError::with_message(start, "Unterminated string")),
+        }
+    }
+}
+
+fn scan_number(
+    starting_char: char,
+    line: u32,
+    iter: &mut ScannerIterator,
+) -> Result<String, ParserError> {
     let mut string = String::new();
     string.push(starting_char);
+
+    let radix = match (starting_char, iter.peek()) {
+        ('0', Some((_, 'x'))) | ('0', Some((_, 'X'))) => 16,
+        ('0', Some((_, 'o'))) | ('0', Some((_, 'O'))) => 8,
+        ('0', Some((_, 'b'))) | ('0', Some((_, 'B'))) => 2,
+        _ => 10,
+    };
+
+    if radix != 10 {
+        let (_, prefix) = iter.next().unwrap();
+        string.push(prefix);
+        if scan_digits(radix, line, &mut string, iter)? == 0 {
+            return Err(malformed_number(line, &string));
+        }
+    } else {
+        scan_digits(10, line, &mut string, iter)?;
+
+        // A dot only belongs to the number when a digit follows, so `1..2` and `1.foo` still lex
+        let mut is_fractional = false;
+        if let Some((_, '.')) = iter.peek() {
+            if char_after_next(iter).is_some_and(|c| c.is_ascii_digit()) {
+                iter.next();
+                string.push('.');
+                scan_digits(10, line, &mut string, iter)?;
+                is_fractional = true;
+            }
+        }
+
+        let mut has_exponent = false;
+        if let Some((_, 'e')) | Some((_, 'E')) = iter.peek() {
+            let (_, e) = iter.next().unwrap();
+            string.push(e);
+            if let Some((_, '+')) | Some((_, '-')) = iter.peek() {
+                let (_, sign) = iter.next().unwrap();
+                string.push(sign);
+            }
+            if scan_digits(10, line, &mut string, iter)? == 0 {
+                return Err(malformed_number(line, &string));
+            }
+            has_exponent = true;
+        }
+
+        if let Some((_, '.')) = iter.peek() {
+            if (is_fractional || has_exponent) && char_after_next(iter) != Some('.') {
+                string.push('.');
+                return Err(malformed_number(line, &string));
+            }
+        }
+
+        // A trailing `d` marks a decimal literal such as 12.34d
+        if let Some((_, 'd')) = iter.peek() {
+            iter.next();
+            string.push('d');
+            // Decimals hold 28 or so significant digits, so longer literals don't fit
+            let digits = string[..string.len() - 1].replace('_', "");
+            if has_exponent || lox_decimal::parse(&digits).is_err() {
+                return Err(malformed_number(line, &string));
+            }
+        }
+    }
+
+    match iter.peek() {
+        Some((_, c)) if c.is_alphanumeric() || *c == '_' => {
+            string.push(*c);
+            Err(malformed_number(line, &string))
+        }
+        _ => Ok(string),
+    }
+}
+
+// Consumes digits in the given radix, allowing single `_` separators between them
+fn scan_digits(
+    radix: u32,
+    line: u32,
+    string: &mut String,
+    iter: &mut ScannerIterator,
+) -> Result<usize, ParserError> {
+    let mut count = 0;
     while let Some((_, c)) = iter.peek() {
-        if *c != '.' && !c.is_numeric() {
+        if c.is_digit(radix) {
+            let (_, digit) = iter.next().unwrap();
+            string.push(digit);
+            count += 1;
+        } else if *c == '_' {
+            let follows_digit = string.chars().last().is_some_and(|c| c.is_digit(radix));
+            iter.next();
+            string.push('_');
+            let precedes_digit = iter.peek().is_some_and(|(_, c)| c.is_digit(radix));
+            if !follows_digit || !precedes_digit {
+                return Err(malformed_number(line, string));
+            }
+        } else {
             break;
         }
-        let (_, number) = iter.next().unwrap();
-        string.push(number);
     }
-    string
+    Ok(count)
+}
+
+fn char_after_next(iter: &ScannerIterator) -> Option<char> {
+    let mut ahead = iter.clone();
+    ahead.next();
+    ahead.peek().map(|(_, c)| *c)
+}
+
+fn malformed_number(line: u32, lexeme: &str) -> ParserError {
+    ParserError::with_message(line, &format!("Malformed number literal '{}'", lexeme))
 }
 
 fn scan_identifier(starting_char: char, iter: &mut ScannerIterator) -> (String, TokenType) {
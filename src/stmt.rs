@@ -1,10 +1,14 @@
 use std::fmt::Debug;
+use uuid::Uuid;
 
-use crate::expr::BoxedExpr;
+use crate::expr::{BoxedExpr, Variable};
+use crate::lox_error::LoxError;
 use crate::lox_value::LoxValue;
+use crate::pattern::Pattern;
 use crate::token::Token;
 
 pub type BoxedStmt = Box<dyn Stmt>;
+pub type StmtResult = Result<Option<LoxValue>, LoxError>;
 
 pub trait Stmt: CloneableStmt
 where
@@ -38,15 +42,23 @@ pub trait Visitor {
     fn visit_print_statement(&mut self, stmt: &Print) -> Self::Value;
     fn visit_expression_statement(&mut self, stmt: &Expression) -> Self::Value;
     fn visit_var_statement(&mut self, stmt: &Var) -> Self::Value;
+    fn visit_var_pattern_statement(&mut self, stmt: &VarPattern) -> Self::Value;
     fn visit_block_statement(&mut self, stmt: &Block) -> Self::Value;
     fn visit_if_statement(&mut self, stmt: &If) -> Self::Value;
     fn visit_while_statement(&mut self, stmt: &While) -> Self::Value;
+    fn visit_for_in_statement(&mut self, stmt: &ForIn) -> Self::Value;
     fn visit_function_statement(&mut self, stmt: &Function) -> Self::Value;
     fn visit_return_statement(&mut self, stmt: &Return) -> Self::Value;
+    fn visit_yield_statement(&mut self, stmt: &Yield) -> Self::Value;
+    fn visit_class_statement(&mut self, stmt: &Class) -> Self::Value;
+    fn visit_break_statement(&mut self, stmt: &Break) -> Self::Value;
+    fn visit_continue_statement(&mut self, stmt: &Continue) -> Self::Value;
+    fn visit_try_statement(&mut self, stmt: &Try) -> Self::Value;
+    fn visit_throw_statement(&mut self, stmt: &Throw) -> Self::Value;
 }
 
 pub trait Visitable {
-    fn accept(&self, visitor: &mut Visitor<Value = Option<LoxValue>>) -> Option<LoxValue>;
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult;
 }
 
 #[derive(Debug, Clone)]
@@ -67,7 +79,7 @@ impl Expression {
 }
 
 impl Visitable for Expression {
-    fn accept(&self, visitor: &mut Visitor<Value = Option<LoxValue>>) -> Option<LoxValue> {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
         visitor.visit_expression_statement(self)
     }
 }
@@ -90,7 +102,7 @@ impl Print {
 }
 
 impl Visitable for Print {
-    fn accept(&self, visitor: &mut Visitor<Value = Option<LoxValue>>) -> Option<LoxValue> {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
         visitor.visit_print_statement(self)
     }
 }
@@ -99,13 +111,18 @@ impl Visitable for Print {
 pub struct Var {
     name: Token,
     initializer: Option<BoxedExpr>,
+    constant: bool,
 }
 
 impl Stmt for Var {}
 
 impl Var {
-    pub fn new(name: Token, initializer: Option<BoxedExpr>) -> Box<dyn Stmt> {
-        Box::new(Var { name, initializer })
+    pub fn new(name: Token, initializer: Option<BoxedExpr>, constant: bool) -> Box<dyn Stmt> {
+        Box::new(Var {
+            name,
+            initializer,
+            constant,
+        })
     }
 
     pub fn name(&self) -> Token {
@@ -115,14 +132,56 @@ impl Var {
     pub fn initializer(&self) -> Option<BoxedExpr> {
         self.initializer.clone()
     }
+
+    pub fn constant(&self) -> bool {
+        self.constant
+    }
 }
 
 impl Visitable for Var {
-    fn accept(&self, visitor: &mut Visitor<Value = Option<LoxValue>>) -> Option<LoxValue> {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
         visitor.visit_var_statement(self)
     }
 }
 
+// A `var` or `const` declaration that destructures its initializer
+#[derive(Debug, Clone)]
+pub struct VarPattern {
+    pattern: Pattern,
+    initializer: BoxedExpr,
+    constant: bool,
+}
+
+impl Stmt for VarPattern {}
+
+impl VarPattern {
+    pub fn new(pattern: Pattern, initializer: BoxedExpr, constant: bool) -> Box<dyn Stmt> {
+        Box::new(VarPattern {
+            pattern,
+            initializer,
+            constant,
+        })
+    }
+
+    pub fn pattern(&self) -> Pattern {
+        self.pattern.clone()
+    }
+
+    pub fn initializer(&self) -> BoxedExpr {
+        self.initializer.clone()
+    }
+
+    pub fn constant(&self) -> bool {
+        self.constant
+    }
+}
+
+impl Visitable for VarPattern {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
+        visitor.visit_var_pattern_statement(self)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Block {
     statements: Vec<BoxedStmt>,
@@ -131,7 +190,7 @@ pub struct Block {
 impl Stmt for Block {}
 
 impl Visitable for Block {
-    fn accept(&self, visitor: &mut Visitor<Value = Option<LoxValue>>) -> Option<LoxValue> {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
         visitor.visit_block_statement(self)
     }
 }
@@ -156,7 +215,7 @@ pub struct If {
 impl Stmt for If {}
 
 impl Visitable for If {
-    fn accept(&self, visitor: &mut Visitor<Value = Option<LoxValue>>) -> Option<LoxValue> {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
         visitor.visit_if_statement(self)
     }
 }
@@ -191,19 +250,37 @@ impl If {
 pub struct While {
     condition: BoxedExpr,
     body: BoxedStmt,
+    increment: Option<BoxedExpr>,
 }
 
 impl Stmt for While {}
 
 impl Visitable for While {
-    fn accept(&self, visitor: &mut Visitor<Value = Option<LoxValue>>) -> Option<LoxValue> {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
         visitor.visit_while_statement(self)
     }
 }
 
 impl While {
     pub fn new(condition: BoxedExpr, body: BoxedStmt) -> BoxedStmt {
-        Box::new(While { condition, body })
+        Box::new(While {
+            condition,
+            body,
+            increment: None,
+        })
+    }
+
+    // Used by desugared `for` loops so the increment still runs after a `continue`
+    pub fn with_increment(
+        condition: BoxedExpr,
+        body: BoxedStmt,
+        increment: Option<BoxedExpr>,
+    ) -> BoxedStmt {
+        Box::new(While {
+            condition,
+            body,
+            increment,
+        })
     }
 
     pub fn condition(&self) -> BoxedExpr {
@@ -213,26 +290,102 @@ impl While {
     pub fn body(&self) -> BoxedStmt {
         self.body.clone()
     }
+
+    pub fn increment(&self) -> Option<BoxedExpr> {
+        self.increment.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ForIn {
+    pattern: Pattern,
+    keyword: Token,
+    iterable: BoxedExpr,
+    body: BoxedStmt,
+    constant: bool,
+}
+
+impl Stmt for ForIn {}
+
+impl Visitable for ForIn {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
+        visitor.visit_for_in_statement(self)
+    }
+}
+
+impl ForIn {
+    pub fn new(
+        pattern: Pattern,
+        keyword: Token,
+        iterable: BoxedExpr,
+        body: BoxedStmt,
+        constant: bool,
+    ) -> BoxedStmt {
+        Box::new(ForIn {
+            pattern,
+            keyword,
+            iterable,
+            body,
+            constant,
+        })
+    }
+
+    pub fn pattern(&self) -> Pattern {
+        self.pattern.clone()
+    }
+
+    pub fn keyword(&self) -> Token {
+        self.keyword.clone()
+    }
+
+    pub fn iterable(&self) -> BoxedExpr {
+        self.iterable.clone()
+    }
+
+    pub fn body(&self) -> BoxedStmt {
+        self.body.clone()
+    }
+
+    pub fn constant(&self) -> bool {
+        self.constant
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Function {
+    id: Uuid,
     name: Token,
     params: Vec<Token>,
     body: Vec<BoxedStmt>,
+    generator: bool,
 }
 
 impl Stmt for Function {}
 
 impl Visitable for Function {
-    fn accept(&self, visitor: &mut Visitor<Value = Option<LoxValue>>) -> Option<LoxValue> {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
         visitor.visit_function_statement(self)
     }
 }
 
 impl Function {
-    pub fn new(name: Token, params: Vec<Token>, body: Vec<BoxedStmt>) -> BoxedStmt {
-        Box::new(Function { name, params, body })
+    pub fn new(
+        name: Token,
+        params: Vec<Token>,
+        body: Vec<BoxedStmt>,
+        generator: bool,
+    ) -> Box<Function> {
+        Box::new(Function {
+            id: Uuid::new_v4(),
+            name,
+            params,
+            body,
+            generator,
+        })
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
     }
 
     pub fn name(&self) -> Token {
@@ -246,24 +399,33 @@ impl Function {
     pub fn body(&self) -> Vec<BoxedStmt> {
         self.body.clone()
     }
+
+    pub fn doc(&self) -> Option<String> {
+        self.name.doc()
+    }
+
+    // Whether the body contains a `yield`, making calls return a generator
+    pub fn generator(&self) -> bool {
+        self.generator
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Return {
     keyword: Token,
-    value: BoxedExpr,
+    value: Option<BoxedExpr>,
 }
 
 impl Stmt for Return {}
 
 impl Visitable for Return {
-    fn accept(&self, visitor: &mut Visitor<Value = Option<LoxValue>>) -> Option<LoxValue> {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
         visitor.visit_return_statement(self)
     }
 }
 
 impl Return {
-    pub fn new(keyword: Token, value: BoxedExpr) -> BoxedStmt {
+    pub fn new(keyword: Token, value: Option<BoxedExpr>) -> BoxedStmt {
         Box::new(Return { keyword, value })
     }
 
@@ -271,6 +433,204 @@ impl Return {
         self.keyword.clone()
     }
 
+    pub fn value(&self) -> Option<BoxedExpr> {
+        self.value.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Yield {
+    keyword: Token,
+    value: Option<BoxedExpr>,
+}
+
+impl Stmt for Yield {}
+
+impl Visitable for Yield {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
+        visitor.visit_yield_statement(self)
+    }
+}
+
+impl Yield {
+    pub fn new(keyword: Token, value: Option<BoxedExpr>) -> BoxedStmt {
+        Box::new(Yield { keyword, value })
+    }
+
+    pub fn keyword(&self) -> Token {
+        self.keyword.clone()
+    }
+
+    pub fn value(&self) -> Option<BoxedExpr> {
+        self.value.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Class {
+    name: Token,
+    superclass: Option<Variable>,
+    methods: Vec<Function>,
+}
+
+impl Stmt for Class {}
+
+impl Visitable for Class {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
+        visitor.visit_class_statement(self)
+    }
+}
+
+impl Class {
+    pub fn new(name: Token, superclass: Option<Variable>, methods: Vec<Function>) -> BoxedStmt {
+        Box::new(Class {
+            name,
+            superclass,
+            methods,
+        })
+    }
+
+    pub fn name(&self) -> Token {
+        self.name.clone()
+    }
+
+    pub fn superclass(&self) -> Option<Variable> {
+        self.superclass.clone()
+    }
+
+    pub fn methods(&self) -> Vec<Function> {
+        self.methods.clone()
+    }
+
+    pub fn doc(&self) -> Option<String> {
+        self.name.doc()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Break {
+    keyword: Token,
+}
+
+impl Stmt for Break {}
+
+impl Visitable for Break {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
+        visitor.visit_break_statement(self)
+    }
+}
+
+impl Break {
+    pub fn new(keyword: Token) -> BoxedStmt {
+        Box::new(Break { keyword })
+    }
+
+    pub fn keyword(&self) -> Token {
+        self.keyword.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Continue {
+    keyword: Token,
+}
+
+impl Stmt for Continue {}
+
+impl Visitable for Continue {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
+        visitor.visit_continue_statement(self)
+    }
+}
+
+impl Continue {
+    pub fn new(keyword: Token) -> BoxedStmt {
+        Box::new(Continue { keyword })
+    }
+
+    pub fn keyword(&self) -> Token {
+        self.keyword.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Try {
+    keyword: Token,
+    body: Vec<BoxedStmt>,
+    catch_name: Option<Token>,
+    catch_body: Option<Vec<BoxedStmt>>,
+    finally_body: Option<Vec<BoxedStmt>>,
+}
+
+impl Stmt for Try {}
+
+impl Visitable for Try {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
+        visitor.visit_try_statement(self)
+    }
+}
+
+impl Try {
+    pub fn new(
+        keyword: Token,
+        body: Vec<BoxedStmt>,
+        catch_name: Option<Token>,
+        catch_body: Option<Vec<BoxedStmt>>,
+        finally_body: Option<Vec<BoxedStmt>>,
+    ) -> BoxedStmt {
+        Box::new(Try {
+            keyword,
+            body,
+            catch_name,
+            catch_body,
+            finally_body,
+        })
+    }
+
+    pub fn keyword(&self) -> Token {
+        self.keyword.clone()
+    }
+
+    pub fn body(&self) -> Vec<BoxedStmt> {
+        self.body.clone()
+    }
+
+    pub fn catch_name(&self) -> Option<Token> {
+        self.catch_name.clone()
+    }
+
+    pub fn catch_body(&self) -> Option<Vec<BoxedStmt>> {
+        self.catch_body.clone()
+    }
+
+    pub fn finally_body(&self) -> Option<Vec<BoxedStmt>> {
+        self.finally_body.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Throw {
+    keyword: Token,
+    value: BoxedExpr,
+}
+
+impl Stmt for Throw {}
+
+impl Visitable for Throw {
+    fn accept(&self, visitor: &mut Visitor<Value = StmtResult>) -> StmtResult {
+        visitor.visit_throw_statement(self)
+    }
+}
+
+impl Throw {
+    pub fn new(keyword: Token, value: BoxedExpr) -> BoxedStmt {
+        Box::new(Throw { keyword, value })
+    }
+
+    pub fn keyword(&self) -> Token {
+        self.keyword.clone()
+    }
+
     pub fn value(&self) -> BoxedExpr {
         self.value.clone()
     }
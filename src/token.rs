@@ -1,5 +1,6 @@
 use std::fmt;
 use std::fmt::Display;
+use std::rc::Rc;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum TokenType {
@@ -8,6 +9,9 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
+    Colon,
     Comma,
     Dot,
     Minus,
@@ -15,8 +19,15 @@ pub enum TokenType {
     Semicolon,
     Slash,
     Star,
+    Percent,
+    Ampersand,
+    Pipe,
+    Caret,
+    Tilde,
+    Question,
 
     // One or two character tokens.
+    Arrow,
     Bang,
     BangEqual,
     Equal,
@@ -25,29 +36,56 @@ pub enum TokenType {
     GreaterEqual,
     Less,
     LessEqual,
+    LessLess,
+    GreaterGreater,
+    StarStar,
+    TildeSlash,
+    PlusEqual,
+    PlusPlus,
+    MinusEqual,
+    MinusMinus,
+    StarEqual,
+    SlashEqual,
+    PercentEqual,
+    QuestionQuestion,
+    QuestionDot,
+    Ellipsis,
+    DotDot,
+    DotDotEqual,
 
     // Literals.
     Identifier,
     String,
+    Interpolation,
+    DocComment,
     Number,
 
     // Keywords.
     And,
+    Break,
+    Catch,
     Class,
+    Const,
+    Continue,
     Else,
     False,
+    Finally,
     Fun,
     For,
     If,
+    In,
     Nil,
     Or,
     Print,
     Return,
     Super,
     This,
+    Throw,
     True,
+    Try,
     Var,
     While,
+    Yield,
 
     Eof,
 }
@@ -56,11 +94,25 @@ pub enum TokenType {
 pub struct Token {
     lexeme: String,
     token_type: TokenType,
+    line: u32,
+    doc: Option<Rc<String>>,
 }
 
 impl Token {
-    pub fn new(lexeme: String, token_type: TokenType) -> Token {
-        Token { lexeme, token_type }
+    pub fn new(lexeme: String, token_type: TokenType, line: u32) -> Token {
+        Token {
+            lexeme,
+            token_type,
+            line,
+            doc: None,
+        }
+    }
+
+    pub fn with_doc(self, doc: Option<String>) -> Token {
+        Token {
+            doc: doc.map(Rc::new),
+            ..self
+        }
     }
 
     pub fn token_type(&self) -> TokenType {
@@ -70,6 +122,14 @@ impl Token {
     pub fn lexeme(&self) -> String {
         self.lexeme.clone()
     }
+
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    pub fn doc(&self) -> Option<String> {
+        self.doc.as_ref().map(|doc| doc.to_string())
+    }
 }
 
 impl Display for Token {